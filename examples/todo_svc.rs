@@ -44,7 +44,7 @@ struct Service {
     select_one: serde_odbc::Statement<serde_odbc::Params<i32>, serde_odbc::Cols<PersistentTodo>>,
     insert: serde_odbc::Statement<serde_odbc::Params<PersistentTodo>, serde_odbc::NoCols>,
     last_rowid: serde_odbc::Statement<serde_odbc::NoParams, serde_odbc::Cols<i32>>,
-    update: serde_odbc::Statement<serde_odbc::Params<(PersistentTodo, i32)>, serde_odbc::NoCols>,
+    update: serde_odbc::Statement<serde_odbc::Params<PersistentTodo>, serde_odbc::NoCols>,
 }
 
 fn to_string<N: generic_array::ArrayLength<u8>>(value: &serde_odbc::String<N>) -> String {
@@ -97,7 +97,7 @@ impl Service {
 
         let update = serde_odbc::Statement::new(
             &conn,
-            "UPDATE todos SET id = ?, text = ?, done = ? WHERE id = ?",
+            "UPDATE todos SET text = :text, done = :done WHERE id = :id",
         )?;
 
         Ok(Service {
@@ -112,15 +112,12 @@ impl Service {
 
     fn get_todos(&mut self) -> Result<HashMap<i32, Todo>, serde_odbc::Error> {
         let trans = self.conn.begin();
-        let stmt = &mut self.select_all;
 
-        stmt.exec()?;
-
-        let mut todos = HashMap::new();
-
-        while stmt.fetch()? {
-            todos.reserve(stmt.cols().len());
-            todos.extend(stmt.cols().iter().map(|todo| {
+        let todos = self
+            .select_all
+            .query_all(())?
+            .into_iter()
+            .map(|todo| {
                 (
                     *todo.id.as_ref().unwrap(),
                     Todo {
@@ -128,8 +125,8 @@ impl Service {
                         done: todo.done,
                     },
                 )
-            }));
-        }
+            })
+            .collect();
 
         trans.commit()?;
 
@@ -138,23 +135,13 @@ impl Service {
 
     fn get_todo(&mut self, id: i32) -> Result<Option<Todo>, serde_odbc::Error> {
         let trans = self.conn.begin();
-        let stmt = &mut self.select_one;
-
-        *stmt.params() = id;
-
-        stmt.exec()?;
-
-        let found = stmt.fetch()?;
+        let todo = self.select_one.query_optional(id)?;
 
         trans.commit()?;
 
-        if !found {
-            return Ok(None);
-        }
-
-        Ok(Some(Todo {
-            text: to_string(&stmt.cols().text),
-            done: stmt.cols().done,
+        Ok(todo.map(|todo| Todo {
+            text: to_string(&todo.text),
+            done: todo.done,
         }))
     }
 
@@ -167,11 +154,7 @@ impl Service {
 
         stmt.exec()?;
 
-        let stmt = &mut self.last_rowid;
-
-        stmt.exec()?;
-
-        let id = if stmt.fetch()? { *stmt.cols() } else { -1 };
+        let id = self.last_rowid.query_one(())?;
 
         trans.commit()?;
 
@@ -182,10 +165,9 @@ impl Service {
         let trans = self.conn.begin();
         let stmt = &mut self.update;
 
-        stmt.params().1 = id;
-        stmt.params().0.id = Some(id).into();
-        from_string(&mut stmt.params().0.text, &todo.text);
-        stmt.params().0.done = todo.done;
+        stmt.params().id = Some(id).into();
+        from_string(&mut stmt.params().text, &todo.text);
+        stmt.params().done = todo.done;
 
         stmt.exec()?;
 
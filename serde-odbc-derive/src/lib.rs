@@ -0,0 +1,301 @@
+/*
+This file is part of serde-odbc.
+
+serde-odbc is free software: you can redistribute it and/or modify
+it under the terms of the GNU Lesser General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+serde-odbc is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Lesser General Public License for more details.
+
+You should have received a copy of the GNU Lesser General Public License
+along with serde-odbc.  If not, see <http://www.gnu.org/licenses/>.
+*/
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{
+    parse_macro_input, parse_quote, Data, DeriveInput, Error, Fields, GenericParam, Generics,
+    Ident, Index, Lit, Member, Meta, NestedMeta, Path, Type, TypeParamBound,
+};
+
+#[proc_macro_derive(Bindable)]
+pub fn derive_bindable(input: TokenStream) -> TokenStream {
+    expand(input, bindable)
+}
+
+#[proc_macro_derive(OdbcRow, attributes(odbc))]
+pub fn derive_odbc_row(input: TokenStream) -> TokenStream {
+    expand(input, |input| odbc_struct(input, "OdbcRow", "column_names"))
+}
+
+#[proc_macro_derive(OdbcParams, attributes(odbc))]
+pub fn derive_odbc_params(input: TokenStream) -> TokenStream {
+    expand(input, |input| {
+        odbc_struct(input, "OdbcParams", "param_names")
+    })
+}
+
+fn expand(input: TokenStream, f: fn(DeriveInput) -> Result<TokenStream2, Error>) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    f(input).unwrap_or_else(Error::into_compile_error).into()
+}
+
+struct Field {
+    member: Member,
+    name: String,
+    ty: Type,
+}
+
+fn struct_fields<'a>(input: &'a DeriveInput, derive: &str) -> Result<&'a Fields, Error> {
+    match &input.data {
+        Data::Struct(data) => Ok(&data.fields),
+        Data::Enum(_) => Err(Error::new_spanned(
+            input,
+            format!("{} cannot be derived for enums, use Enum instead", derive),
+        )),
+        Data::Union(_) => Err(Error::new_spanned(
+            input,
+            format!("{} cannot be derived for unions", derive),
+        )),
+    }
+}
+
+fn bindable(input: DeriveInput) -> Result<TokenStream2, Error> {
+    let fields = match struct_fields(&input, "Bindable")? {
+        // Newtype structs are transparent as when binding them via `Serialize`.
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+            let ty = &fields.unnamed[0].ty;
+
+            let body = quote! {
+                <#ty as ::serde_odbc::Bindable>::bind_fields(
+                    binder,
+                    offset + ::core::mem::offset_of!(Self, 0),
+                )?;
+            };
+
+            let generics = add_bounds(&input.generics, &[parse_quote!(::serde_odbc::Bindable)]);
+
+            return Ok(bindable_impl(&input, &generics, body));
+        }
+        fields => fields
+            .iter()
+            .enumerate()
+            .map(|(index, field)| match &field.ident {
+                Some(ident) => Field {
+                    member: Member::Named(ident.clone()),
+                    name: ident.to_string().trim_start_matches("r#").to_owned(),
+                    ty: field.ty.clone(),
+                },
+                None => Field {
+                    member: Member::Unnamed(Index::from(index)),
+                    name: index.to_string(),
+                    ty: field.ty.clone(),
+                },
+            })
+            .collect::<Vec<_>>(),
+    };
+
+    let generics = add_bounds(&input.generics, &[parse_quote!(::serde_odbc::Bindable)]);
+
+    Ok(bindable_impl(&input, &generics, bind_fields(&fields)))
+}
+
+fn odbc_struct(input: DeriveInput, derive: &str, names_fn: &str) -> Result<TokenStream2, Error> {
+    let fields = match struct_fields(&input, derive)? {
+        Fields::Named(fields) => fields
+            .named
+            .iter()
+            .map(|field| {
+                check_type(&field.ty)?;
+
+                let ident = field.ident.clone().unwrap();
+
+                let name = match rename(field)? {
+                    Some(name) => name,
+                    None => ident.to_string().trim_start_matches("r#").to_owned(),
+                };
+
+                Ok(Field {
+                    member: Member::Named(ident),
+                    name,
+                    ty: field.ty.clone(),
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?,
+        _ => {
+            return Err(Error::new_spanned(
+                &input,
+                format!(
+                    "{} can only be derived for structs with named fields",
+                    derive
+                ),
+            ))
+        }
+    };
+
+    let generics = add_bounds(
+        &input.generics,
+        &[
+            parse_quote!(::serde_odbc::SqlField),
+            parse_quote!(::core::default::Default),
+        ],
+    );
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let ident = &input.ident;
+    let trait_ = Ident::new(derive, Span::call_site());
+    let names_fn = Ident::new(names_fn, Span::call_site());
+
+    let bindable = bindable_impl(&input, &generics, bind_fields(&fields));
+
+    let names = fields.iter().map(|field| &field.name);
+
+    let descriptions = fields.iter().map(|field| {
+        let name = &field.name;
+        let ty = &field.ty;
+
+        quote_spanned! {ty.span()=>
+            <#ty as ::serde_odbc::SqlField>::describe(#name)
+        }
+    });
+
+    let members = fields.iter().map(|field| &field.member);
+
+    Ok(quote! {
+        #bindable
+
+        impl #impl_generics ::serde_odbc::#trait_ for #ident #ty_generics #where_clause {
+            fn #names_fn() -> &'static [&'static str] {
+                &[#(#names),*]
+            }
+
+            fn fields() -> ::std::vec::Vec<::serde_odbc::FieldDescription> {
+                ::std::vec![#(#descriptions),*]
+            }
+        }
+
+        impl #impl_generics ::core::default::Default for #ident #ty_generics #where_clause {
+            fn default() -> Self {
+                Self {
+                    #(#members: ::core::default::Default::default(),)*
+                }
+            }
+        }
+    })
+}
+
+fn bind_fields(fields: &[Field]) -> TokenStream2 {
+    fields
+        .iter()
+        .map(|field| {
+            let Field { member, name, ty } = field;
+
+            quote_spanned! {ty.span()=>
+                binder.field::<#ty>(#name, offset + ::core::mem::offset_of!(Self, #member))?;
+            }
+        })
+        .collect()
+}
+
+fn bindable_impl(input: &DeriveInput, generics: &Generics, body: TokenStream2) -> TokenStream2 {
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    quote! {
+        unsafe impl #impl_generics ::serde_odbc::Bindable for #ident #ty_generics #where_clause {
+            fn bind_fields(
+                binder: &mut ::serde_odbc::FieldBinder<'_>,
+                offset: usize,
+            ) -> ::serde_odbc::Result<()> {
+                #body
+
+                Ok(())
+            }
+        }
+    }
+}
+
+fn add_bounds(generics: &Generics, bounds: &[TypeParamBound]) -> Generics {
+    let mut generics = generics.clone();
+
+    for param in &mut generics.params {
+        if let GenericParam::Type(param) = param {
+            param.bounds.extend(bounds.iter().cloned());
+        }
+    }
+
+    generics
+}
+
+fn rename(field: &syn::Field) -> Result<Option<String>, Error> {
+    let mut name = None;
+
+    for attr in &field.attrs {
+        if !attr.path.is_ident("odbc") {
+            continue;
+        }
+
+        let list = match attr.parse_meta()? {
+            Meta::List(list) => list,
+            meta => {
+                return Err(Error::new_spanned(
+                    meta,
+                    "expected #[odbc(rename = \"...\")]",
+                ))
+            }
+        };
+
+        for nested in list.nested {
+            match nested {
+                NestedMeta::Meta(Meta::NameValue(meta)) if meta.path.is_ident("rename") => {
+                    match meta.lit {
+                        Lit::Str(lit) => name = Some(lit.value()),
+                        lit => return Err(Error::new_spanned(lit, "expected a string literal")),
+                    }
+                }
+                nested => return Err(Error::new_spanned(nested, "unknown odbc attribute")),
+            }
+        }
+    }
+
+    Ok(name)
+}
+
+// Catches common mistakes with a helpful message, all other
+// unsupported types fail to implement `SqlField`.
+fn check_type(ty: &Type) -> Result<(), Error> {
+    let message = match ty {
+        Type::Reference(_) | Type::Ptr(_) => {
+            "references cannot be bound as the value must be stored within the struct"
+        }
+        Type::Slice(_) | Type::Array(_) => "arrays cannot be bound, use serde_odbc::String instead",
+        Type::Path(path) if path.qself.is_none() => match last_segment(&path.path) {
+            Some((name, has_args)) => match (name.as_str(), has_args) {
+                ("Option", _) => "Option cannot be bound, use serde_odbc::Nullable instead",
+                ("String", false) | ("str", _) | ("Vec", _) => {
+                    "heap allocated strings cannot be bound, use serde_odbc::String instead"
+                }
+                ("char", _) => "char cannot be bound, use u32 or serde_odbc::String instead",
+                ("usize", _) | ("isize", _) => {
+                    "pointer sized integers cannot be bound, use a fixed size integer instead"
+                }
+                _ => return Ok(()),
+            },
+            None => return Ok(()),
+        },
+        _ => return Ok(()),
+    };
+
+    Err(Error::new_spanned(ty, message))
+}
+
+fn last_segment(path: &Path) -> Option<(String, bool)> {
+    let segment = path.segments.last()?;
+
+    Some((segment.ident.to_string(), !segment.arguments.is_empty()))
+}
@@ -14,11 +14,43 @@ GNU Lesser General Public License for more details.
 You should have received a copy of the GNU Lesser General Public License
 along with serde-odbc.  If not, see <http://www.gnu.org/licenses/>.
 */
-use odbc_sys::{SqlCDataType, SqlDataType};
+use std::mem::size_of;
+
+use odbc_sys::{SqlCDataType, SqlDataType, SQLSMALLINT, SQLULEN};
+
+use crate::datetime::{Date, Time, Timestamp};
 
 pub trait BindTypes {
     fn c_data_type() -> SqlCDataType;
     fn data_type() -> SqlDataType;
+
+    fn column_size() -> SQLULEN {
+        0
+    }
+    fn decimal_digits() -> SQLSMALLINT {
+        0
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct BindType {
+    pub c_data_type: SqlCDataType,
+    pub data_type: SqlDataType,
+    pub column_size: SQLULEN,
+    pub decimal_digits: SQLSMALLINT,
+    pub size: usize,
+}
+
+impl BindType {
+    pub fn of<T: BindTypes>() -> Self {
+        BindType {
+            c_data_type: T::c_data_type(),
+            data_type: T::data_type(),
+            column_size: T::column_size(),
+            decimal_digits: T::decimal_digits(),
+            size: size_of::<T>(),
+        }
+    }
 }
 
 macro_rules! impl_bind_types {
@@ -48,3 +80,46 @@ impl_bind_types!(f32, SQL_C_FLOAT, SQL_FLOAT);
 impl_bind_types!(f64, SQL_C_DOUBLE, SQL_DOUBLE);
 
 impl_bind_types!(bool, SQL_C_BIT, SQL_EXT_TINYINT);
+
+impl BindTypes for Date {
+    fn c_data_type() -> SqlCDataType {
+        SqlCDataType::SQL_C_TYPE_DATE
+    }
+    fn data_type() -> SqlDataType {
+        SqlDataType::SQL_DATE
+    }
+
+    fn column_size() -> SQLULEN {
+        10
+    }
+}
+
+impl BindTypes for Time {
+    fn c_data_type() -> SqlCDataType {
+        SqlCDataType::SQL_C_TYPE_TIME
+    }
+    fn data_type() -> SqlDataType {
+        SqlDataType::SQL_TIME
+    }
+
+    fn column_size() -> SQLULEN {
+        8
+    }
+}
+
+// `yyyy-mm-dd hh:mm:ss.fffffffff` with nanosecond precision
+impl BindTypes for Timestamp {
+    fn c_data_type() -> SqlCDataType {
+        SqlCDataType::SQL_C_TYPE_TIMESTAMP
+    }
+    fn data_type() -> SqlDataType {
+        SqlDataType::SQL_TIMESTAMP
+    }
+
+    fn column_size() -> SQLULEN {
+        29
+    }
+    fn decimal_digits() -> SQLSMALLINT {
+        9
+    }
+}
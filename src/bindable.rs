@@ -0,0 +1,464 @@
+/*
+This file is part of serde-odbc.
+
+serde-odbc is free software: you can redistribute it and/or modify
+it under the terms of the GNU Lesser General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+serde-odbc is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Lesser General Public License for more details.
+
+You should have received a copy of the GNU Lesser General Public License
+along with serde-odbc.  If not, see <http://www.gnu.org/licenses/>.
+*/
+use std::mem::{offset_of, replace, size_of};
+use std::ptr::null_mut;
+
+use odbc_sys::{SQLHSTMT, SQLLEN, SQLPOINTER};
+use serde::ser::Serialize;
+
+use crate::bind_types::BindType;
+use crate::binder::{BinderImpl, FieldPath, SharedIndicators};
+use crate::col_binder::{bind_col_fields, bind_cols, ColOptions};
+use crate::datetime::{Date, Time, Timestamp};
+use crate::error::{Error, Result};
+use crate::metadata::FieldDescription;
+use crate::param_binder::{bind_param_fields, bind_params, ParamOptions};
+
+/// # Safety
+///
+/// `bind_fields` must only bind fields stored within `Self` at `offset`
+/// using the types they are declared with, e.g. by computing offsets via
+/// `offset_of!` as `#[derive(Bindable)]` does.
+pub unsafe trait Bindable {
+    fn bind_fields(binder: &mut FieldBinder<'_>, offset: usize) -> Result<()>;
+}
+
+pub trait SqlField: Bindable {
+    fn describe(name: &'static str) -> FieldDescription;
+}
+
+pub trait OdbcRow: Bindable + Default {
+    fn column_names() -> &'static [&'static str];
+
+    fn fields() -> Vec<FieldDescription>;
+}
+
+pub trait OdbcParams: Bindable + Default {
+    fn param_names() -> &'static [&'static str];
+
+    fn fields() -> Vec<FieldDescription>;
+}
+
+pub struct FieldBinder<'a> {
+    impl_: &'a mut dyn BinderImpl,
+    value_ptr: *mut u8,
+    size: usize,
+    field: FieldPath,
+    indicator_ptr: *mut SQLLEN,
+    length_ptr: *mut SQLLEN,
+    shared: SharedIndicators,
+}
+
+impl FieldBinder<'_> {
+    pub(crate) fn bind<T: Bindable, I: BinderImpl>(
+        mut impl_: I,
+        value: &T,
+    ) -> Result<(I, SharedIndicators)> {
+        let mut binder = FieldBinder {
+            impl_: &mut impl_,
+            value_ptr: (value as *const T) as *mut u8,
+            size: size_of::<T>(),
+            field: Default::default(),
+            indicator_ptr: null_mut(),
+            length_ptr: null_mut(),
+            shared: Default::default(),
+        };
+
+        T::bind_fields(&mut binder, 0)?;
+
+        let shared = binder.shared;
+
+        Ok((impl_, shared))
+    }
+
+    pub fn field<T: Bindable>(&mut self, name: &str, offset: usize) -> Result<()> {
+        self.field.0.push(name.to_owned());
+        let result = T::bind_fields(self, offset);
+        self.field.0.pop();
+
+        result
+    }
+
+    fn check_bounds(&self, offset: usize, size: usize) -> Result<*mut u8> {
+        match offset.checked_add(size) {
+            Some(end) if end <= self.size => Ok(unsafe { self.value_ptr.add(offset) }),
+            _ => Err(Error::Bind(format!(
+                "field {} is not stored within the bound value",
+                self.field
+            ))),
+        }
+    }
+
+    pub(crate) fn bind_value(&mut self, bind_type: BindType, offset: usize) -> Result<()> {
+        let value_ptr = self.check_bounds(offset, bind_type.size)?;
+        let indicator_ptr = replace(&mut self.indicator_ptr, null_mut());
+
+        self.impl_.bind(
+            &self.field,
+            bind_type,
+            value_ptr as SQLPOINTER,
+            indicator_ptr,
+        )
+    }
+
+    pub(crate) fn bind_bytes(&mut self, offset: usize, length: usize) -> Result<()> {
+        // Includes the terminator written by the driver.
+        let value_ptr = self.check_bounds(offset, length + 1)?;
+        let length_ptr = replace(&mut self.length_ptr, null_mut());
+        let indicator_ptr = replace(&mut self.indicator_ptr, null_mut());
+        let nullable = !indicator_ptr.is_null();
+
+        let indicator_ptr = if nullable {
+            if !length_ptr.is_null() {
+                self.shared.push(
+                    length_ptr as usize - self.value_ptr as usize,
+                    indicator_ptr as usize - self.value_ptr as usize,
+                );
+            }
+
+            indicator_ptr
+        } else {
+            length_ptr
+        };
+
+        self.impl_.bind_str(
+            &self.field,
+            length,
+            value_ptr as SQLPOINTER,
+            indicator_ptr,
+            nullable,
+        )
+    }
+
+    pub(crate) fn with_indicator<F>(&mut self, offset: usize, f: F) -> Result<()>
+    where
+        F: FnOnce(&mut Self) -> Result<()>,
+    {
+        self.indicator_ptr = self.check_bounds(offset, size_of::<SQLLEN>())? as *mut SQLLEN;
+        let result = f(self);
+        self.indicator_ptr = null_mut();
+
+        result
+    }
+
+    // Passes the length of the bytes bound next which is kept apart
+    // from the indicator of an enclosing `Nullable`.
+    pub(crate) fn with_length<F>(&mut self, offset: usize, f: F) -> Result<()>
+    where
+        F: FnOnce(&mut Self) -> Result<()>,
+    {
+        self.length_ptr = self.check_bounds(offset, size_of::<SQLLEN>())? as *mut SQLLEN;
+        let result = f(self);
+        self.length_ptr = null_mut();
+
+        result
+    }
+}
+
+macro_rules! impl_bindable {
+    ($($type:ty),*) => {
+        $(
+            unsafe impl Bindable for $type {
+                fn bind_fields(binder: &mut FieldBinder<'_>, offset: usize) -> Result<()> {
+                    binder.bind_value(BindType::of::<$type>(), offset)
+                }
+            }
+
+            impl SqlField for $type {
+                fn describe(name: &'static str) -> FieldDescription {
+                    let bind_type = BindType::of::<$type>();
+
+                    FieldDescription {
+                        name,
+                        c_data_type: bind_type.c_data_type,
+                        data_type: bind_type.data_type,
+                        column_size: bind_type.column_size,
+                        nullable: false,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_bindable!(i8, i16, i32, i64, u8, u16, u32, u64, f32, f64, bool, Date, Time, Timestamp);
+
+macro_rules! impl_bindable_tuple {
+    ($($type:ident $index:tt),*) => {
+        unsafe impl<$($type: Bindable),*> Bindable for ($($type,)*) {
+            fn bind_fields(binder: &mut FieldBinder<'_>, offset: usize) -> Result<()> {
+                $(
+                    binder.field::<$type>(stringify!($index), offset + offset_of!(Self, $index))?;
+                )*
+
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_bindable_tuple!(A 0);
+impl_bindable_tuple!(A 0, B 1);
+impl_bindable_tuple!(A 0, B 1, C 2);
+impl_bindable_tuple!(A 0, B 1, C 2, D 3);
+impl_bindable_tuple!(A 0, B 1, C 2, D 3, E 4);
+impl_bindable_tuple!(A 0, B 1, C 2, D 3, E 4, F 5);
+impl_bindable_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+impl_bindable_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+
+// Selects how `Params`, `ParamSet`, `Cols` and `RowSet` bind their values.
+pub trait BindWith<T> {
+    /// # Safety
+    ///
+    /// `params` must stay in place while `stmt` is executed using these bindings.
+    unsafe fn bind_params(
+        stmt: SQLHSTMT,
+        params: &T,
+        options: &ParamOptions,
+    ) -> Result<(bool, SharedIndicators)>;
+
+    /// # Safety
+    ///
+    /// `cols` must stay in place while `stmt` fetches rows using these bindings.
+    unsafe fn bind_cols(stmt: SQLHSTMT, cols: &T, options: ColOptions) -> Result<SharedIndicators>;
+}
+
+pub struct Serde;
+
+impl<T: Serialize> BindWith<T> for Serde {
+    unsafe fn bind_params(
+        stmt: SQLHSTMT,
+        params: &T,
+        options: &ParamOptions,
+    ) -> Result<(bool, SharedIndicators)> {
+        bind_params(stmt, params, options)
+    }
+
+    unsafe fn bind_cols(stmt: SQLHSTMT, cols: &T, options: ColOptions) -> Result<SharedIndicators> {
+        bind_cols(stmt, cols, options)
+    }
+}
+
+pub struct Offsets;
+
+impl<T: Bindable> BindWith<T> for Offsets {
+    unsafe fn bind_params(
+        stmt: SQLHSTMT,
+        params: &T,
+        options: &ParamOptions,
+    ) -> Result<(bool, SharedIndicators)> {
+        bind_param_fields(stmt, params, options)
+    }
+
+    unsafe fn bind_cols(stmt: SQLHSTMT, cols: &T, options: ColOptions) -> Result<SharedIndicators> {
+        bind_col_fields(stmt, cols, options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use generic_array::typenum::U8;
+    use odbc_sys::{SQL_C_BIT, SQL_C_CHAR, SQL_C_SLONG, SQL_EXT_TINYINT, SQL_INTEGER, SQL_VARCHAR};
+
+    use crate::{
+        col_binding::{Cols, NoCols},
+        connection::{Connection, Environment},
+        nullable::Nullable,
+        param_binding::{NoParams, ParamSet, Params},
+        statement::Statement,
+        string::String,
+        tests::CONN_STR,
+        Bindable, OdbcParams, OdbcRow,
+    };
+
+    #[derive(Clone, Copy, Default, Bindable)]
+    struct Todo {
+        id: i32,
+        text: String<U8>,
+        done: Nullable<bool>,
+    }
+
+    #[derive(Clone, Copy, OdbcRow)]
+    struct TodoRow {
+        id: i32,
+        #[odbc(rename = "text")]
+        title: String<U8>,
+        done: Nullable<bool>,
+    }
+
+    #[derive(Clone, Copy, OdbcParams)]
+    struct TodoFilter {
+        done: bool,
+    }
+
+    #[derive(Clone, Copy, Default)]
+    struct Misplaced {
+        value: i32,
+    }
+
+    unsafe impl Bindable for Misplaced {
+        fn bind_fields(binder: &mut FieldBinder<'_>, offset: usize) -> Result<()> {
+            binder.field::<i64>("value", offset + offset_of!(Self, value))
+        }
+    }
+
+    #[derive(Clone, Copy, Default)]
+    struct Unterminated {
+        value: [u8; 8],
+    }
+
+    unsafe impl Bindable for Unterminated {
+        fn bind_fields(binder: &mut FieldBinder<'_>, offset: usize) -> Result<()> {
+            binder.bind_bytes(offset + offset_of!(Self, value), 8)
+        }
+    }
+
+    #[test]
+    fn bind_fields() {
+        let env = Environment::new().unwrap();
+        let conn = Connection::new(&env, CONN_STR).unwrap();
+
+        {
+            let mut stmt: Statement<NoParams, NoCols> = Statement::new(
+                &conn,
+                "CREATE TEMPORARY TABLE todos (id INTEGER NOT NULL, text TEXT NOT NULL, done BOOLEAN)",
+            )
+            .unwrap();
+            stmt.exec().unwrap();
+        }
+
+        {
+            let mut stmt: Statement<ParamSet<Todo, Offsets>, NoCols> =
+                Statement::new(&conn, "INSERT INTO todos (id, text, done) VALUES (?, ?, ?)")
+                    .unwrap();
+            for id in 0..2 {
+                let mut todo = Todo {
+                    id,
+                    done: Some(id == 1).into(),
+                    ..Default::default()
+                };
+                todo.text.extend_from_slice(b"todo");
+                stmt.params().push(todo);
+            }
+            stmt.exec().unwrap();
+        }
+
+        {
+            let mut stmt: Statement<Params<i32, Offsets>, Cols<Todo, Offsets>> =
+                Statement::new(&conn, "SELECT id, text, done FROM todos WHERE id = ?").unwrap();
+            let todo = stmt.query_one(1).unwrap();
+            assert_eq!(1, todo.id);
+            assert_eq!(&b"todo"[..], todo.text.as_slice());
+            assert_eq!(Some(&true), todo.done.as_ref());
+        }
+
+        {
+            let mut stmt: Statement<NoParams, Cols<Misplaced, Offsets>> =
+                Statement::new(&conn, "SELECT 1").unwrap();
+            match stmt.exec() {
+                Err(Error::Bind(_)) => (),
+                _ => panic!("misplaced field not rejected"),
+            }
+        }
+
+        {
+            let mut stmt: Statement<NoParams, Cols<Unterminated, Offsets>> =
+                Statement::new(&conn, "SELECT 'foo'").unwrap();
+            match stmt.exec() {
+                Err(Error::Bind(_)) => (),
+                _ => panic!("missing room for terminator not rejected"),
+            }
+        }
+    }
+
+    #[test]
+    fn derive_rows_and_params() {
+        assert_eq!(&["id", "text", "done"][..], TodoRow::column_names());
+        assert_eq!(
+            vec![
+                FieldDescription {
+                    name: "id",
+                    c_data_type: SQL_C_SLONG,
+                    data_type: SQL_INTEGER,
+                    column_size: 0,
+                    nullable: false,
+                },
+                FieldDescription {
+                    name: "text",
+                    c_data_type: SQL_C_CHAR,
+                    data_type: SQL_VARCHAR,
+                    column_size: 8,
+                    nullable: false,
+                },
+                FieldDescription {
+                    name: "done",
+                    c_data_type: SQL_C_BIT,
+                    data_type: SQL_EXT_TINYINT,
+                    column_size: 0,
+                    nullable: true,
+                },
+            ],
+            TodoRow::fields()
+        );
+
+        assert_eq!(&["done"][..], TodoFilter::param_names());
+
+        let row = TodoRow::default();
+        assert_eq!(&b""[..], row.title.as_slice());
+        assert_eq!(None, row.done.as_ref());
+
+        let env = Environment::new().unwrap();
+        let conn = Connection::new(&env, CONN_STR).unwrap();
+
+        {
+            let mut stmt: Statement<NoParams, NoCols> = Statement::new(
+                &conn,
+                "CREATE TEMPORARY TABLE todos (id INTEGER NOT NULL, text TEXT NOT NULL, done BOOLEAN)",
+            )
+            .unwrap();
+            stmt.exec().unwrap();
+        }
+
+        {
+            let mut stmt: Statement<ParamSet<TodoRow, Offsets>, NoCols> =
+                Statement::new(&conn, "INSERT INTO todos (id, text, done) VALUES (?, ?, ?)")
+                    .unwrap();
+            for id in 0..2 {
+                let mut row = TodoRow {
+                    id,
+                    done: Some(id == 1).into(),
+                    ..Default::default()
+                };
+                row.title.extend_from_slice(b"todo");
+                stmt.params().push(row);
+            }
+            stmt.exec().unwrap();
+        }
+
+        {
+            let mut stmt: Statement<Params<TodoFilter, Offsets>, Cols<TodoRow, Offsets>> =
+                Statement::new(&conn, "SELECT done, text, id FROM todos WHERE done = ?").unwrap();
+            stmt.set_bind_cols_by_name(true);
+            let row = stmt.query_one(TodoFilter { done: true }).unwrap();
+            assert_eq!(1, row.id);
+            assert_eq!(&b"todo"[..], row.title.as_slice());
+            assert_eq!(Some(&true), row.done.as_ref());
+        }
+    }
+}
@@ -15,28 +15,83 @@ You should have received a copy of the GNU Lesser General Public License
 along with serde-odbc.  If not, see <http://www.gnu.org/licenses/>.
 */
 use std::cell::Cell;
+use std::fmt;
+use std::mem::{size_of, size_of_val};
 use std::ptr::null_mut;
+use std::thread::LocalKey;
 
-use odbc_sys::{SQLLEN, SQLPOINTER};
-use serde::ser::{Impossible, Serialize, SerializeStruct, SerializeTuple, Serializer};
+use odbc_sys::{InputOutput, SQLLEN, SQLPOINTER, SQL_NULL_DATA, SQL_PARAM_INPUT};
+use serde::ser::{
+    Impossible, Serialize, SerializeMap, SerializeStruct, SerializeTuple, SerializeTupleStruct,
+    Serializer,
+};
 
-use crate::bind_types::BindTypes;
+use crate::bind_types::BindType;
 use crate::error::{Error, Result};
 
 thread_local! {
-    static INDICATOR_PTR: Cell<*mut SQLLEN> = Cell::new(null_mut());
+    static INDICATOR_PTR: Cell<*mut SQLLEN> = const { Cell::new(null_mut()) };
+    static LENGTH_PTR: Cell<*mut SQLLEN> = const { Cell::new(null_mut()) };
+    static INPUT_OUTPUT: Cell<InputOutput> = const { Cell::new(SQL_PARAM_INPUT) };
+    static BIND_TYPE: Cell<Option<BindType>> = const { Cell::new(None) };
 }
 
 fn take_indicator() -> *mut SQLLEN {
     INDICATOR_PTR.with(|indicator_ptr| indicator_ptr.replace(null_mut()))
 }
 
+fn take_length() -> *mut SQLLEN {
+    LENGTH_PTR.with(|length_ptr| length_ptr.replace(null_mut()))
+}
+
+fn take_bind_type() -> Option<BindType> {
+    BIND_TYPE.with(Cell::take)
+}
+
+// Binds the whole value passed to the next `serialize_newtype_struct` as a single field.
+pub fn with_bind_type<F, T>(value: BindType, f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    BIND_TYPE.with(|bind_type| {
+        bind_type.set(Some(value));
+
+        struct Reset<'a>(&'a Cell<Option<BindType>>);
+
+        impl Drop for Reset<'_> {
+            fn drop(&mut self) {
+                self.0.set(None);
+            }
+        }
+
+        let _reset = Reset(bind_type);
+
+        f()
+    })
+}
+
 pub fn with_indicator<F, T>(indicator: *mut SQLLEN, f: F) -> T
 where
     F: FnOnce() -> T,
 {
-    INDICATOR_PTR.with(|indicator_ptr| {
-        indicator_ptr.set(indicator);
+    with_ptr(&INDICATOR_PTR, indicator, f)
+}
+
+// Passes the length of the bytes serialized next which is kept apart
+// from the indicator of an enclosing `Nullable`.
+pub fn with_length<F, T>(length: *mut SQLLEN, f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    with_ptr(&LENGTH_PTR, length, f)
+}
+
+fn with_ptr<F, T>(key: &'static LocalKey<Cell<*mut SQLLEN>>, value: *mut SQLLEN, f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    key.with(|ptr| {
+        ptr.set(value);
 
         struct Reset<'a>(&'a Cell<*mut SQLLEN>);
 
@@ -46,25 +101,124 @@ where
             }
         }
 
-        let _reset = Reset(indicator_ptr);
+        let _reset = Reset(ptr);
+
+        f()
+    })
+}
+
+pub fn input_output() -> InputOutput {
+    INPUT_OUTPUT.with(Cell::get)
+}
+
+pub fn with_input_output<F, T>(value: InputOutput, f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    INPUT_OUTPUT.with(|input_output| {
+        let last_value = input_output.replace(value);
+
+        struct Reset<'a>(&'a Cell<InputOutput>, InputOutput);
+
+        impl Drop for Reset<'_> {
+            fn drop(&mut self) {
+                self.0.set(self.1);
+            }
+        }
+
+        let _reset = Reset(input_output, last_value);
 
         f()
     })
 }
 
+#[derive(Default)]
+pub struct FieldPath(pub(crate) Vec<String>);
+
+impl FieldPath {
+    pub fn name(&self) -> Option<&str> {
+        self.0.last().map(String::as_str)
+    }
+}
+
+impl fmt::Display for FieldPath {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        if self.0.is_empty() {
+            return write!(fmt, "<value>");
+        }
+
+        write!(fmt, "{}", self.0.join("."))
+    }
+}
+
+// A `Nullable` wrapping a value with a length of its own, i.e. a `String`, binds
+// its indicator in place of that length. Both are kept in sync using their
+// offsets within the bound value.
+#[derive(Default)]
+pub struct SharedIndicators {
+    offsets: Vec<(usize, usize)>,
+    has_output: bool,
+}
+
+impl SharedIndicators {
+    pub(crate) fn push(&mut self, length_offset: usize, indicator_offset: usize) {
+        self.offsets.push((length_offset, indicator_offset));
+        self.has_output |= input_output() != SQL_PARAM_INPUT;
+    }
+
+    // Whether any of these are written by the driver when executing a statement.
+    pub fn has_output(&self) -> bool {
+        self.has_output
+    }
+
+    /// # Safety
+    ///
+    /// `value_ptr` must point to a value of the type these offsets were collected for.
+    pub unsafe fn read(&self, value_ptr: *mut u8) {
+        for &(length_offset, indicator_offset) in &self.offsets {
+            let indicator = *(value_ptr.add(indicator_offset) as *const SQLLEN);
+
+            if indicator != SQL_NULL_DATA {
+                *(value_ptr.add(length_offset) as *mut SQLLEN) = indicator;
+            }
+        }
+    }
+
+    /// # Safety
+    ///
+    /// `value_ptr` must point to a value of the type these offsets were collected for.
+    pub unsafe fn write(&self, value_ptr: *mut u8) {
+        for &(length_offset, indicator_offset) in &self.offsets {
+            let indicator = value_ptr.add(indicator_offset) as *mut SQLLEN;
+
+            if *indicator != SQL_NULL_DATA {
+                *indicator = *(value_ptr.add(length_offset) as *const SQLLEN);
+            }
+        }
+    }
+}
+
 pub trait BinderImpl {
-    fn bind<T: BindTypes>(
+    fn bind(
         &mut self,
+        field: &FieldPath,
+        bind_type: BindType,
         value_ptr: SQLPOINTER,
         indicator_ptr: *mut SQLLEN,
     ) -> Result<()>;
 
     fn bind_str(
         &mut self,
+        field: &FieldPath,
         length: usize,
         value_ptr: SQLPOINTER,
         indicator_ptr: *mut SQLLEN,
+        nullable: bool,
     ) -> Result<()>;
+
+    fn bind_null(&mut self, field: &FieldPath) -> Result<()>;
+
+    fn value_dependent(&mut self, field: &FieldPath) -> Result<()>;
 }
 
 pub struct Binder<I: BinderImpl> {
@@ -72,10 +226,12 @@ pub struct Binder<I: BinderImpl> {
     lower_bound: SQLPOINTER,
     upper_bound: SQLPOINTER,
     value_ptr: SQLPOINTER,
+    field: FieldPath,
+    shared: SharedIndicators,
 }
 
 impl<I: BinderImpl> Binder<I> {
-    pub fn bind<T: Serialize>(impl_: I, value: &T) -> Result<()> {
+    pub fn bind<T: Serialize>(impl_: I, value: &T) -> Result<(I, SharedIndicators)> {
         let value_ptr = (value as *const T) as *mut T;
 
         let mut binder = Binder {
@@ -83,33 +239,78 @@ impl<I: BinderImpl> Binder<I> {
             lower_bound: value_ptr as SQLPOINTER,
             upper_bound: unsafe { value_ptr.add(1) } as SQLPOINTER,
             value_ptr: value_ptr as SQLPOINTER,
+            field: Default::default(),
+            shared: Default::default(),
         };
 
-        value.serialize(&mut binder)
+        value.serialize(&mut binder)?;
+
+        Ok((binder.impl_, binder.shared))
+    }
+
+    fn unsupported(&self, shape: &'static str) -> Error {
+        Error::Unsupported(self.field.to_string(), shape)
+    }
+
+    fn check_bounds(&self, value_ptr: SQLPOINTER, size: usize) -> Result<()> {
+        let end_ptr = (value_ptr as usize).checked_add(size);
+
+        if value_ptr < self.lower_bound || end_ptr > Some(self.upper_bound as usize) {
+            return Err(Error::Bind(format!(
+                "field {} is not stored within the bound value",
+                self.field
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn set_value<T: ?Sized>(&mut self, value: &T) -> Result<()> {
+        let value_ptr = ((value as *const T) as *mut T) as SQLPOINTER;
+
+        self.check_bounds(value_ptr, size_of_val(value))?;
+
+        self.value_ptr = value_ptr;
+
+        Ok(())
+    }
+
+    fn serialize_nested<T: ?Sized + Serialize>(&mut self, name: String, value: &T) -> Result<()> {
+        self.field.0.push(name);
+        let result = self
+            .set_value(value)
+            .and_then(|()| value.serialize(&mut *self));
+        self.field.0.pop();
+
+        result
     }
 }
 
 macro_rules! fn_serialize {
     ($method:ident, $type:ident) => {
         fn $method(self, _value: $type) -> Result<()> {
-            assert!(self.lower_bound <= self.value_ptr);
-            assert!(unsafe  { (self.value_ptr as *mut $type).add(1) as SQLPOINTER } <= self.upper_bound);
+            self.check_bounds(self.value_ptr, size_of::<$type>())?;
 
-            self.impl_.bind::<$type>(self.value_ptr, take_indicator())
+            self.impl_.bind(
+                &self.field,
+                BindType::of::<$type>(),
+                self.value_ptr,
+                take_indicator(),
+            )
         }
-    }
+    };
 }
 
 impl<'a, I: BinderImpl> Serializer for &'a mut Binder<I> {
     type Ok = ();
     type Error = Error;
 
-    type SerializeTuple = Self;
-    type SerializeTupleStruct = Impossible<Self::Ok, Self::Error>;
+    type SerializeTuple = TupleBinder<'a, I>;
+    type SerializeTupleStruct = TupleBinder<'a, I>;
     type SerializeTupleVariant = Impossible<Self::Ok, Self::Error>;
     type SerializeStruct = Self;
     type SerializeStructVariant = Impossible<Self::Ok, Self::Error>;
-    type SerializeMap = Impossible<Self::Ok, Self::Error>;
+    type SerializeMap = MapBinder<'a, I>;
     type SerializeSeq = Impossible<Self::Ok, Self::Error>;
 
     fn_serialize!(serialize_i8, i8);
@@ -130,37 +331,63 @@ impl<'a, I: BinderImpl> Serializer for &'a mut Binder<I> {
     fn serialize_bytes(self, value: &[u8]) -> Result<()> {
         let value_ptr = (value.as_ptr() as *mut u8) as SQLPOINTER;
 
-        assert!(self.lower_bound <= value_ptr);
-        assert!(
-            unsafe { (value_ptr as *mut u8).add(value.len()) } as SQLPOINTER <= self.upper_bound
-        );
+        // Includes the terminator written by the driver.
+        self.check_bounds(value_ptr, value.len() + 1)?;
+
+        let length_ptr = take_length();
+        let indicator_ptr = take_indicator();
+        let nullable = !indicator_ptr.is_null();
+
+        let indicator_ptr = if nullable {
+            if !length_ptr.is_null() {
+                for ptr in [length_ptr, indicator_ptr] {
+                    self.check_bounds(ptr as SQLPOINTER, size_of::<SQLLEN>())?;
+                }
+
+                let lower_bound = self.lower_bound as usize;
+                self.shared.push(
+                    length_ptr as usize - lower_bound,
+                    indicator_ptr as usize - lower_bound,
+                );
+            }
+
+            indicator_ptr
+        } else {
+            length_ptr
+        };
 
         self.impl_
-            .bind_str(value.len(), value_ptr, take_indicator())
+            .bind_str(&self.field, value.len(), value_ptr, indicator_ptr, nullable)
     }
 
     fn serialize_char(self, _value: char) -> Result<()> {
-        unimplemented!();
+        Err(self.unsupported("char"))
     }
 
     fn serialize_str(self, _value: &str) -> Result<()> {
-        unimplemented!();
+        Err(self.unsupported("str"))
     }
 
     fn serialize_none(self) -> Result<()> {
-        unimplemented!();
+        self.impl_.value_dependent(&self.field)?;
+
+        self.impl_.bind_null(&self.field)
     }
 
-    fn serialize_some<T: ?Sized + Serialize>(self, _value: &T) -> Result<()> {
-        unimplemented!();
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<()> {
+        self.impl_.value_dependent(&self.field)?;
+
+        self.set_value(value)?;
+
+        value.serialize(self)
     }
 
     fn serialize_unit(self) -> Result<()> {
-        unimplemented!();
+        Err(self.unsupported("unit"))
     }
 
     fn serialize_unit_struct(self, _name: &'static str) -> Result<()> {
-        unimplemented!();
+        Err(self.unsupported("unit struct"))
     }
 
     fn serialize_unit_variant(
@@ -169,15 +396,25 @@ impl<'a, I: BinderImpl> Serializer for &'a mut Binder<I> {
         _variant_index: u32,
         _variant: &'static str,
     ) -> Result<()> {
-        unimplemented!();
+        Err(self.unsupported("unit variant"))
     }
 
     fn serialize_newtype_struct<T: ?Sized + Serialize>(
         self,
         _name: &'static str,
-        _value: &T,
+        value: &T,
     ) -> Result<()> {
-        unimplemented!();
+        if let Some(bind_type) = take_bind_type() {
+            self.check_bounds(self.value_ptr, bind_type.size)?;
+
+            return self
+                .impl_
+                .bind(&self.field, bind_type, self.value_ptr, take_indicator());
+        }
+
+        self.set_value(value)?;
+
+        value.serialize(self)
     }
 
     fn serialize_newtype_variant<T: ?Sized + Serialize>(
@@ -187,19 +424,22 @@ impl<'a, I: BinderImpl> Serializer for &'a mut Binder<I> {
         _variant: &'static str,
         _value: &T,
     ) -> Result<()> {
-        unimplemented!();
+        Err(self.unsupported("newtype variant"))
     }
 
     fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
-        Ok(self)
+        Ok(TupleBinder {
+            binder: self,
+            index: 0,
+        })
     }
 
     fn serialize_tuple_struct(
         self,
         _name: &'static str,
-        _len: usize,
+        len: usize,
     ) -> Result<Self::SerializeTupleStruct> {
-        unimplemented!();
+        self.serialize_tuple(len)
     }
 
     fn serialize_tuple_variant(
@@ -209,7 +449,7 @@ impl<'a, I: BinderImpl> Serializer for &'a mut Binder<I> {
         _variant: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeTupleVariant> {
-        unimplemented!();
+        Err(self.unsupported("tuple variant"))
     }
 
     fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct> {
@@ -223,26 +463,48 @@ impl<'a, I: BinderImpl> Serializer for &'a mut Binder<I> {
         _variant: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeStructVariant> {
-        unimplemented!();
+        Err(self.unsupported("struct variant"))
     }
 
     fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
-        unimplemented!();
+        Err(self.unsupported("seq"))
     }
 
     fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
-        unimplemented!();
+        Ok(MapBinder {
+            binder: self,
+            key: None,
+        })
     }
 }
 
-impl<'a, I: BinderImpl> SerializeTuple for &'a mut Binder<I> {
+pub struct TupleBinder<'a, I: BinderImpl> {
+    binder: &'a mut Binder<I>,
+    index: usize,
+}
+
+impl<'a, I: BinderImpl> SerializeTuple for TupleBinder<'a, I> {
     type Ok = ();
     type Error = Error;
 
     fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
-        self.value_ptr = ((value as *const T) as *mut T) as SQLPOINTER;
+        let index = self.index;
+        self.index += 1;
+
+        self.binder.serialize_nested(index.to_string(), value)
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a, I: BinderImpl> SerializeTupleStruct for TupleBinder<'a, I> {
+    type Ok = ();
+    type Error = Error;
 
-        value.serialize(&mut **self)
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        SerializeTuple::serialize_element(self, value)
     }
 
     fn end(self) -> Result<()> {
@@ -256,15 +518,568 @@ impl<'a, I: BinderImpl> SerializeStruct for &'a mut Binder<I> {
 
     fn serialize_field<T: ?Sized + Serialize>(
         &mut self,
-        _name: &'static str,
+        name: &'static str,
         value: &T,
     ) -> Result<()> {
-        self.value_ptr = ((value as *const T) as *mut T) as SQLPOINTER;
+        self.serialize_nested(name.to_owned(), value)
+    }
+
+    fn skip_field(&mut self, name: &'static str) -> Result<()> {
+        self.field.0.push(name.to_owned());
+        let err = Error::Bind(format!(
+            "field {} is skipped conditionally which would shift the following fields",
+            self.field
+        ));
+        self.field.0.pop();
 
-        value.serialize(&mut **self)
+        Err(err)
     }
 
     fn end(self) -> Result<()> {
         Ok(())
     }
 }
+
+// Structs with flattened fields are serialized as maps keyed by field name.
+pub struct MapBinder<'a, I: BinderImpl> {
+    binder: &'a mut Binder<I>,
+    key: Option<String>,
+}
+
+impl<'a, I: BinderImpl> SerializeMap for MapBinder<'a, I> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<()> {
+        self.key = Some(key.serialize(KeySerializer(&self.binder.field))?);
+
+        Ok(())
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        let key = self
+            .key
+            .take()
+            .expect("serialize_key must precede serialize_value");
+
+        self.binder.serialize_nested(key, value)
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+struct KeySerializer<'a>(&'a FieldPath);
+
+impl KeySerializer<'_> {
+    fn unsupported(&self) -> Error {
+        Error::Unsupported(self.0.to_string(), "map with non-string keys")
+    }
+}
+
+macro_rules! fn_reject_key {
+    ($($method:ident($($type:ty),*)),* $(,)?) => {
+        $(
+            fn $method(self, $(_: $type),*) -> Result<String> {
+                Err(self.unsupported())
+            }
+        )*
+    };
+}
+
+impl Serializer for KeySerializer<'_> {
+    type Ok = String;
+    type Error = Error;
+
+    type SerializeTuple = Impossible<String, Error>;
+    type SerializeTupleStruct = Impossible<String, Error>;
+    type SerializeTupleVariant = Impossible<String, Error>;
+    type SerializeStruct = Impossible<String, Error>;
+    type SerializeStructVariant = Impossible<String, Error>;
+    type SerializeMap = Impossible<String, Error>;
+    type SerializeSeq = Impossible<String, Error>;
+
+    fn serialize_str(self, value: &str) -> Result<String> {
+        Ok(value.to_owned())
+    }
+
+    fn_reject_key!(
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_f32(f32),
+        serialize_f64(f64),
+        serialize_bool(bool),
+        serialize_char(char),
+        serialize_bytes(&[u8]),
+        serialize_none(),
+        serialize_unit(),
+        serialize_unit_struct(&'static str),
+        serialize_unit_variant(&'static str, u32, &'static str),
+    );
+
+    fn serialize_some<T: ?Sized + Serialize>(self, _value: &T) -> Result<String> {
+        Err(self.unsupported())
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<String> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<String> {
+        Err(self.unsupported())
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
+        Err(self.unsupported())
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
+        Err(self.unsupported())
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        Err(self.unsupported())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        Err(self.unsupported())
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
+        Err(self.unsupported())
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct> {
+        Err(self.unsupported())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        Err(self.unsupported())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::BTreeMap;
+    use std::result;
+
+    use generic_array::typenum::U8;
+    use odbc_sys::{
+        SqlCDataType, SQL_C_BIT, SQL_C_CHAR, SQL_C_DOUBLE, SQL_C_FLOAT, SQL_C_SBIGINT, SQL_C_SLONG,
+        SQL_C_SSHORT, SQL_C_STINYINT, SQL_C_TYPE_DATE, SQL_C_TYPE_TIMESTAMP, SQL_C_UBIGINT,
+        SQL_C_ULONG, SQL_C_USHORT, SQL_C_UTINYINT,
+    };
+    use serde::Serialize;
+
+    use crate::datetime::{Date, Timestamp};
+    use crate::input_output::Out;
+    use crate::nullable::Nullable;
+    use crate::string::String;
+
+    #[derive(Default)]
+    struct Recorder {
+        binds: Vec<(std::string::String, SqlCDataType, usize)>,
+        nulls: Vec<std::string::String>,
+        value_dependent: bool,
+    }
+
+    impl BinderImpl for Recorder {
+        fn bind(
+            &mut self,
+            field: &FieldPath,
+            bind_type: BindType,
+            value_ptr: SQLPOINTER,
+            _indicator_ptr: *mut SQLLEN,
+        ) -> Result<()> {
+            self.binds
+                .push((field.to_string(), bind_type.c_data_type, value_ptr as usize));
+            Ok(())
+        }
+
+        fn bind_str(
+            &mut self,
+            field: &FieldPath,
+            _length: usize,
+            value_ptr: SQLPOINTER,
+            _indicator_ptr: *mut SQLLEN,
+            _nullable: bool,
+        ) -> Result<()> {
+            self.binds
+                .push((field.to_string(), SQL_C_CHAR, value_ptr as usize));
+            Ok(())
+        }
+
+        fn bind_null(&mut self, field: &FieldPath) -> Result<()> {
+            self.nulls.push(field.to_string());
+            Ok(())
+        }
+
+        fn value_dependent(&mut self, _field: &FieldPath) -> Result<()> {
+            self.value_dependent = true;
+            Ok(())
+        }
+    }
+
+    fn bind<T: Serialize>(value: &T) -> Result<Recorder> {
+        Binder::bind(Recorder::default(), value).map(|(recorder, _)| recorder)
+    }
+
+    fn addr<T>(value: &T) -> usize {
+        value as *const T as usize
+    }
+
+    #[derive(Serialize)]
+    struct Field<T> {
+        field: T,
+    }
+
+    fn unsupported<T: Serialize>(value: T) -> (std::string::String, &'static str) {
+        match bind(&Field { field: value }) {
+            Err(Error::Unsupported(field, shape)) => (field, shape),
+            _ => panic!("unsupported type not rejected"),
+        }
+    }
+
+    #[test]
+    fn bind_primitives() {
+        #[derive(Default, Serialize)]
+        struct Prims {
+            a: i8,
+            b: i16,
+            c: i32,
+            d: i64,
+            e: u8,
+            f: u16,
+            g: u32,
+            h: u64,
+            i: f32,
+            j: f64,
+            k: bool,
+        }
+
+        let value = Prims::default();
+
+        assert_eq!(
+            vec![
+                ("a".to_owned(), SQL_C_STINYINT, addr(&value.a)),
+                ("b".to_owned(), SQL_C_SSHORT, addr(&value.b)),
+                ("c".to_owned(), SQL_C_SLONG, addr(&value.c)),
+                ("d".to_owned(), SQL_C_SBIGINT, addr(&value.d)),
+                ("e".to_owned(), SQL_C_UTINYINT, addr(&value.e)),
+                ("f".to_owned(), SQL_C_USHORT, addr(&value.f)),
+                ("g".to_owned(), SQL_C_ULONG, addr(&value.g)),
+                ("h".to_owned(), SQL_C_UBIGINT, addr(&value.h)),
+                ("i".to_owned(), SQL_C_FLOAT, addr(&value.i)),
+                ("j".to_owned(), SQL_C_DOUBLE, addr(&value.j)),
+                ("k".to_owned(), SQL_C_BIT, addr(&value.k)),
+            ],
+            bind(&value).unwrap().binds
+        );
+    }
+
+    #[test]
+    fn bind_datetime() {
+        #[derive(Default, Serialize)]
+        struct Times {
+            date: Date,
+            ts: Nullable<Timestamp>,
+        }
+
+        let value = Times {
+            ts: Some(Timestamp::default()).into(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            vec![
+                ("date".to_owned(), SQL_C_TYPE_DATE, addr(&value.date)),
+                (
+                    "ts".to_owned(),
+                    SQL_C_TYPE_TIMESTAMP,
+                    addr(value.ts.as_ref().unwrap()),
+                ),
+            ],
+            bind(&value).unwrap().binds
+        );
+    }
+
+    #[test]
+    fn bind_bytes() {
+        let value = Field {
+            field: String::<U8>::default(),
+        };
+
+        let binds = bind(&value).unwrap().binds;
+        assert_eq!(1, binds.len());
+        assert_eq!("field", binds[0].0);
+        assert_eq!(SQL_C_CHAR, binds[0].1);
+    }
+
+    #[test]
+    fn share_nullable_bytes_indicator() {
+        let value = (String::<U8>::default(), Nullable::<String<U8>>::default());
+
+        let (recorder, shared) = Binder::bind(Recorder::default(), &value).unwrap();
+        assert_eq!(2, recorder.binds.len());
+        assert_eq!(1, shared.offsets.len());
+        assert!(!shared.has_output());
+
+        let value = Out::new(Nullable::<String<U8>>::default());
+
+        let (_, shared) = Binder::bind(Recorder::default(), &value).unwrap();
+        assert_eq!(1, shared.offsets.len());
+        assert!(shared.has_output());
+    }
+
+    #[test]
+    fn reject_unterminated_bytes() {
+        struct Bytes([u8; 8]);
+
+        impl Serialize for Bytes {
+            fn serialize<S: serde::Serializer>(
+                &self,
+                serializer: S,
+            ) -> result::Result<S::Ok, S::Error> {
+                serializer.serialize_bytes(&self.0)
+            }
+        }
+
+        match bind(&Bytes([0; 8])) {
+            Err(Error::Bind(_)) => (),
+            _ => panic!("missing room for terminator not rejected"),
+        }
+    }
+
+    #[test]
+    fn bind_newtype_struct() {
+        #[derive(Serialize)]
+        struct Id(i64);
+
+        let value = Field { field: Id(42) };
+
+        assert_eq!(
+            vec![("field".to_owned(), SQL_C_SBIGINT, addr(&value.field.0))],
+            bind(&value).unwrap().binds
+        );
+    }
+
+    #[test]
+    fn bind_tuple_struct() {
+        #[derive(Serialize)]
+        struct Point(f64, f64);
+
+        let value = Field {
+            field: Point(1.0, 2.0),
+        };
+
+        assert_eq!(
+            vec![
+                ("field.0".to_owned(), SQL_C_DOUBLE, addr(&value.field.0)),
+                ("field.1".to_owned(), SQL_C_DOUBLE, addr(&value.field.1)),
+            ],
+            bind(&value).unwrap().binds
+        );
+    }
+
+    #[test]
+    fn reject_out_of_bounds() {
+        struct Doubled(i32);
+
+        // Serializes a temporary which is nested within the bound field.
+        impl Serialize for Doubled {
+            fn serialize<S: serde::Serializer>(
+                &self,
+                serializer: S,
+            ) -> result::Result<S::Ok, S::Error> {
+                Field { field: self.0 * 2 }.serialize(serializer)
+            }
+        }
+
+        #[derive(Serialize)]
+        struct Computed {
+            value: Doubled,
+        }
+
+        match bind(&Computed { value: Doubled(21) }) {
+            Err(Error::Bind(msg)) => assert!(msg.contains("field value.field ")),
+            _ => panic!("value outside of bounds not rejected"),
+        }
+    }
+
+    #[test]
+    fn bind_skipped_flattened_and_renamed_fields() {
+        #[derive(Default, Serialize)]
+        struct Inner {
+            b: i32,
+            #[serde(rename = "C")]
+            c: i64,
+        }
+
+        #[derive(Default, Serialize)]
+        struct Outer {
+            a: i32,
+            #[serde(skip)]
+            _skipped: i32,
+            #[serde(flatten)]
+            inner: Inner,
+            d: bool,
+        }
+
+        let value = Outer::default();
+
+        assert_eq!(
+            vec![
+                ("a".to_owned(), SQL_C_SLONG, addr(&value.a)),
+                ("b".to_owned(), SQL_C_SLONG, addr(&value.inner.b)),
+                ("C".to_owned(), SQL_C_SBIGINT, addr(&value.inner.c)),
+                ("d".to_owned(), SQL_C_BIT, addr(&value.d)),
+            ],
+            bind(&value).unwrap().binds
+        );
+    }
+
+    #[test]
+    fn reject_conditionally_skipped_fields() {
+        #[derive(Serialize)]
+        struct Row {
+            #[serde(skip_serializing_if = "is_zero")]
+            a: i32,
+            b: i32,
+        }
+
+        fn is_zero(value: &i32) -> bool {
+            *value == 0
+        }
+
+        assert_eq!(2, bind(&Row { a: 1, b: 2 }).unwrap().binds.len());
+
+        match bind(&Row { a: 0, b: 2 }) {
+            Err(Error::Bind(msg)) => assert!(msg.contains("field a ")),
+            _ => panic!("conditionally skipped field not rejected"),
+        }
+    }
+
+    #[test]
+    fn reject_map_values_outside_bounds() {
+        let value = Field {
+            field: BTreeMap::from([("a".to_owned(), 1)]),
+        };
+
+        match bind(&value) {
+            Err(Error::Bind(msg)) => assert!(msg.contains("field field.a ")),
+            _ => panic!("map value outside of bounds not rejected"),
+        }
+    }
+
+    #[test]
+    fn bind_tuples() {
+        let value = (1_i32, (2_i64, true));
+
+        assert_eq!(
+            vec![
+                ("0".to_owned(), SQL_C_SLONG, addr(&value.0)),
+                ("1.0".to_owned(), SQL_C_SBIGINT, addr(&value.1 .0)),
+                ("1.1".to_owned(), SQL_C_BIT, addr(&value.1 .1)),
+            ],
+            bind(&value).unwrap().binds
+        );
+    }
+
+    #[test]
+    fn bind_options() {
+        let value = (Some(1_i32), None::<i32>);
+
+        let recorder = bind(&value).unwrap();
+        assert!(recorder.value_dependent);
+        assert_eq!(
+            vec![("0".to_owned(), SQL_C_SLONG, addr(value.0.as_ref().unwrap()))],
+            recorder.binds
+        );
+        assert_eq!(vec!["1".to_owned()], recorder.nulls);
+    }
+
+    #[test]
+    fn reject_unsupported() {
+        #[derive(Serialize)]
+        struct Unit;
+
+        #[derive(Serialize)]
+        enum Enum {
+            Unit,
+            Newtype(i32),
+            Tuple(i32, i32),
+            Struct { value: i32 },
+        }
+
+        assert_eq!(("field".to_owned(), "char"), unsupported('c'));
+        assert_eq!(("field".to_owned(), "str"), unsupported("str"));
+        assert_eq!(("field".to_owned(), "unit"), unsupported(()));
+        assert_eq!(("field".to_owned(), "unit struct"), unsupported(Unit));
+        assert_eq!(
+            ("field".to_owned(), "unit variant"),
+            unsupported(Enum::Unit)
+        );
+        assert_eq!(
+            ("field".to_owned(), "newtype variant"),
+            unsupported(Enum::Newtype(0))
+        );
+        assert_eq!(
+            ("field".to_owned(), "tuple variant"),
+            unsupported(Enum::Tuple(0, 0))
+        );
+        assert_eq!(
+            ("field".to_owned(), "struct variant"),
+            unsupported(Enum::Struct { value: 0 })
+        );
+        assert_eq!(("field".to_owned(), "seq"), unsupported(vec![0]));
+        assert_eq!(
+            ("field".to_owned(), "map with non-string keys"),
+            unsupported(BTreeMap::from([(1, 2)]))
+        );
+
+        assert_eq!(
+            ("field.field".to_owned(), "char"),
+            unsupported(Field { field: 'c' })
+        );
+
+        match bind(&Field { field: 0_i128 }) {
+            Err(Error::Serde(_)) => (),
+            _ => panic!("128-bit integer not rejected"),
+        }
+    }
+}
@@ -0,0 +1,210 @@
+/*
+This file is part of serde-odbc.
+
+serde-odbc is free software: you can redistribute it and/or modify
+it under the terms of the GNU Lesser General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+serde-odbc is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Lesser General Public License for more details.
+
+You should have received a copy of the GNU Lesser General Public License
+along with serde-odbc.  If not, see <http://www.gnu.org/licenses/>.
+*/
+use std::any::Any;
+use std::mem::take;
+use std::ops::{Deref, DerefMut};
+
+use super::col_binding::ColBinding;
+use super::connection::Connection;
+use super::param_binding::ParamBinding;
+use super::statement::Statement;
+
+pub const DEFAULT_CAPACITY: usize = 32;
+
+pub struct StatementCache {
+    capacity: usize,
+    entries: Vec<(String, Box<dyn Any>)>,
+}
+
+impl StatementCache {
+    pub fn new(capacity: usize) -> Self {
+        StatementCache {
+            capacity,
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+
+        if self.entries.len() > capacity {
+            self.entries.drain(..self.entries.len() - capacity);
+        }
+    }
+
+    pub fn take(&mut self, stmt_str: &str) -> Option<Box<dyn Any>> {
+        let pos = self.entries.iter().rposition(|(key, _)| key == stmt_str)?;
+
+        Some(self.entries.remove(pos).1)
+    }
+
+    pub fn put(&mut self, stmt_str: String, stmt: Box<dyn Any>) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        if let Some(pos) = self.entries.iter().position(|(key, _)| *key == stmt_str) {
+            self.entries.remove(pos);
+        } else if self.entries.len() == self.capacity {
+            self.entries.remove(0);
+        }
+
+        self.entries.push((stmt_str, stmt));
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+pub struct CachedStatement<'conn, P: ParamBinding + 'static, C: ColBinding + 'static> {
+    conn: &'conn Connection,
+    stmt_str: String,
+    stmt: Option<Statement<P, C>>,
+}
+
+impl<'conn, P: ParamBinding + 'static, C: ColBinding + 'static> CachedStatement<'conn, P, C> {
+    pub(crate) fn new(conn: &'conn Connection, stmt_str: String, stmt: Statement<P, C>) -> Self {
+        CachedStatement {
+            conn,
+            stmt_str,
+            stmt: Some(stmt),
+        }
+    }
+}
+
+impl<P: ParamBinding + 'static, C: ColBinding + 'static> Deref for CachedStatement<'_, P, C> {
+    type Target = Statement<P, C>;
+
+    fn deref(&self) -> &Self::Target {
+        self.stmt.as_ref().unwrap()
+    }
+}
+
+impl<P: ParamBinding + 'static, C: ColBinding + 'static> DerefMut for CachedStatement<'_, P, C> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.stmt.as_mut().unwrap()
+    }
+}
+
+impl<P: ParamBinding + 'static, C: ColBinding + 'static> Drop for CachedStatement<'_, P, C> {
+    fn drop(&mut self) {
+        let mut stmt = self.stmt.take().unwrap();
+
+        if stmt.reset().is_ok() {
+            self.conn
+                .statement_cache()
+                .put(take(&mut self.stmt_str), Box::new(stmt));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        col_binding::{Cols, RowSet},
+        connection::{Connection, Environment},
+        error::Error,
+        param_binding::{NoParams, Params},
+        tests::CONN_STR,
+    };
+
+    #[test]
+    fn reuse_cached_stmts() {
+        let env = Environment::new().unwrap();
+        let conn = Connection::new(&env, CONN_STR).unwrap();
+
+        let handle = {
+            let mut stmt = conn
+                .prepare_cached::<Params<i32>, Cols<i32>>("SELECT ? + 1")
+                .unwrap();
+            *stmt.params() = 1;
+            stmt.exec().unwrap();
+            assert!(stmt.fetch().unwrap());
+            assert_eq!(2, *stmt.cols());
+            stmt.handle()
+        };
+
+        {
+            let mut stmt = conn
+                .prepare_cached::<Params<i32>, Cols<i32>>("SELECT ? + 1")
+                .unwrap();
+            assert_eq!(handle, stmt.handle());
+            *stmt.params() = 2;
+            stmt.exec().unwrap();
+            assert!(stmt.fetch().unwrap());
+            assert_eq!(3, *stmt.cols());
+        }
+
+        match conn.prepare_cached::<NoParams, Cols<i32>>("SELECT ? + 1") {
+            Err(Error::Bind(_)) => (),
+            _ => panic!("type mismatch not detected"),
+        }
+
+        let handle = conn
+            .prepare_cached::<NoParams, Cols<i32>>("SELECT 1")
+            .unwrap()
+            .handle();
+
+        conn.set_statement_cache_capacity(1);
+        assert_eq!(1, conn.statement_cache_capacity());
+
+        assert_eq!(
+            handle,
+            conn.prepare_cached::<NoParams, Cols<i32>>("SELECT 1")
+                .unwrap()
+                .handle()
+        );
+    }
+
+    #[test]
+    fn reset_cached_stmts() {
+        let env = Environment::new().unwrap();
+        let conn = Connection::new(&env, CONN_STR).unwrap();
+
+        let stmt_str = "SELECT 1 UNION ALL SELECT 2";
+
+        {
+            let mut stmt = conn
+                .prepare_cached::<NoParams, RowSet<i32>>(stmt_str)
+                .unwrap();
+            stmt.set_max_rows(Some(1)).unwrap();
+            stmt.set_fetch_size(4);
+            stmt.set_validate_cols(true);
+            stmt.exec().unwrap();
+            assert!(stmt.fetch().unwrap());
+            assert_eq!(&[1][..], &stmt.cols()[..]);
+        }
+
+        {
+            let mut stmt = conn
+                .prepare_cached::<NoParams, RowSet<i32>>(stmt_str)
+                .unwrap();
+            assert_eq!(None, stmt.max_rows());
+            assert_eq!(0, stmt.fetch_size());
+            assert!(!stmt.validate_cols());
+            stmt.set_fetch_size(4);
+            stmt.exec().unwrap();
+            assert!(stmt.fetch().unwrap());
+            assert_eq!(&[1, 2][..], &stmt.cols()[..]);
+        }
+    }
+}
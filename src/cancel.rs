@@ -0,0 +1,179 @@
+/*
+This file is part of serde-odbc.
+
+serde-odbc is free software: you can redistribute it and/or modify
+it under the terms of the GNU Lesser General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+serde-odbc is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Lesser General Public License for more details.
+
+You should have received a copy of the GNU Lesser General Public License
+along with serde-odbc.  If not, see <http://www.gnu.org/licenses/>.
+*/
+use std::mem::replace;
+use std::ptr::null_mut;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use odbc_sys::{SQLCancel, SQLHSTMT};
+
+use crate::error::{Error, OdbcResult, Result};
+
+struct Handle {
+    stmt: SQLHSTMT,
+    cancelled: bool,
+}
+
+unsafe impl Send for Handle {}
+
+#[derive(Clone)]
+pub struct CancelHandle(Arc<Mutex<Handle>>);
+
+impl CancelHandle {
+    pub(crate) fn new(stmt: SQLHSTMT) -> Self {
+        CancelHandle(Arc::new(Mutex::new(Handle {
+            stmt,
+            cancelled: false,
+        })))
+    }
+
+    pub(crate) fn invalidate(&self) {
+        self.lock().stmt = null_mut();
+    }
+
+    pub(crate) fn reset(&self) {
+        self.lock().cancelled = false;
+    }
+
+    // Not all drivers report calls failing due to a cancellation using HY008.
+    // Only the call which just returned is affected, so the request is cleared.
+    pub(crate) fn check(&self, result: Result<()>) -> Result<()> {
+        let cancelled = replace(&mut self.lock().cancelled, false);
+
+        match result {
+            Err(_) if cancelled => Err(Error::Cancelled),
+            result => result,
+        }
+    }
+
+    pub fn cancel(&self) -> Result<()> {
+        let mut handle = self.lock();
+
+        if handle.stmt.is_null() {
+            return Ok(());
+        }
+
+        unsafe { SQLCancel(handle.stmt) }.check()?;
+        handle.cancelled = true;
+
+        Ok(())
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Handle> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::thread;
+    use std::time::Duration;
+
+    use super::*;
+
+    use crate::{
+        col_binding::Cols,
+        connection::{Connection, Environment},
+        param_binding::NoParams,
+        statement::Statement,
+        tests::CONN_STR,
+    };
+
+    fn is_send_and_sync<T: Send + Sync>(_: &T) {}
+
+    #[test]
+    fn cancel_stmt() {
+        let env = Environment::new().unwrap();
+        let conn = Connection::new(&env, CONN_STR).unwrap();
+
+        let handle = {
+            let mut stmt: Statement<NoParams, Cols<i32>> =
+                Statement::new(&conn, "SELECT 42").unwrap();
+            stmt.set_query_timeout(Some(Duration::from_secs(1)))
+                .unwrap();
+            stmt.exec().unwrap();
+            assert!(stmt.fetch().unwrap());
+            assert_eq!(42, *stmt.cols());
+
+            let handle = stmt.cancel_handle();
+            is_send_and_sync(&handle);
+            handle.cancel().unwrap();
+            handle
+        };
+
+        handle.cancel().unwrap();
+    }
+
+    #[test]
+    fn cancel_running_stmt() {
+        let env = Environment::new().unwrap();
+        let conn = Connection::new(&env, CONN_STR).unwrap();
+
+        let mut stmt: Statement<NoParams, Cols<i64>> = Statement::new(
+            &conn,
+            "WITH RECURSIVE cnt(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM cnt) SELECT COUNT(*) FROM cnt",
+        )
+        .unwrap();
+
+        let handle = stmt.cancel_handle();
+        let done = Arc::new(AtomicBool::new(false));
+
+        let canceller = {
+            let done = done.clone();
+
+            thread::spawn(move || {
+                // Repeated as the statement might not be running yet.
+                while !done.load(Ordering::SeqCst) {
+                    thread::sleep(Duration::from_millis(10));
+                    handle.cancel().unwrap();
+                }
+            })
+        };
+
+        let result = stmt.exec().and_then(|()| stmt.fetch());
+
+        done.store(true, Ordering::SeqCst);
+        canceller.join().unwrap();
+
+        match result {
+            Err(Error::Cancelled) => (),
+            _ => panic!("cancellation not reported"),
+        }
+    }
+
+    #[test]
+    fn ignore_late_cancel() {
+        let env = Environment::new().unwrap();
+        let conn = Connection::new(&env, CONN_STR).unwrap();
+
+        // Computing the second row overflows after the first one was fetched.
+        let mut stmt: Statement<NoParams, Cols<i64>> = Statement::new(
+            &conn,
+            "SELECT 1 UNION ALL SELECT ABS(-9223372036854775807 - 1)",
+        )
+        .unwrap();
+        stmt.exec().unwrap();
+        assert!(stmt.fetch().unwrap());
+
+        stmt.cancel_handle().cancel().unwrap();
+
+        match stmt.fetch() {
+            Err(Error::Odbc(_)) => (),
+            _ => panic!("unrelated failure not reported"),
+        }
+    }
+}
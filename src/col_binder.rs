@@ -14,39 +14,164 @@ GNU Lesser General Public License for more details.
 You should have received a copy of the GNU Lesser General Public License
 along with serde-odbc.  If not, see <http://www.gnu.org/licenses/>.
 */
-use std::mem::size_of;
-
-use odbc_sys::{SQLBindCol, SQLHSTMT, SQLLEN, SQLPOINTER, SQLUSMALLINT, SQL_C_CHAR};
+use odbc_sys::{SQLBindCol, SqlCDataType, SQLHSTMT, SQLLEN, SQLPOINTER, SQLUSMALLINT, SQL_C_CHAR};
 use serde::ser::Serialize;
 
-use super::bind_types::BindTypes;
-use super::binder::{Binder, BinderImpl};
-use super::error::{OdbcResult, Result};
+use super::bind_types::BindType;
+use super::bindable::{Bindable, FieldBinder};
+use super::binder::{Binder, BinderImpl, FieldPath, SharedIndicators};
+use super::error::{Error, OdbcResult, Result};
+use super::metadata::{describe_cols, is_compatible, ColumnDescription};
+
+#[derive(Clone, Copy, Default)]
+pub struct ColOptions {
+    pub validate: bool,
+    pub by_name: bool,
+}
 
 struct ColBinder {
     stmt: SQLHSTMT,
-    col_nr: SQLUSMALLINT,
+    options: ColOptions,
+    descriptions: Vec<ColumnDescription>,
+    bound_cols: Vec<SQLUSMALLINT>,
+}
+
+pub unsafe fn bind_cols<C: Serialize>(
+    stmt: SQLHSTMT,
+    cols: &C,
+    options: ColOptions,
+) -> Result<SharedIndicators> {
+    bind_cols_with(stmt, options, |binder| Binder::bind(binder, cols))
 }
 
-pub unsafe fn bind_cols<C: Serialize>(stmt: SQLHSTMT, cols: &C) -> Result<()> {
-    Binder::bind(ColBinder { stmt, col_nr: 0 }, cols)
+pub unsafe fn bind_col_fields<C: Bindable>(
+    stmt: SQLHSTMT,
+    cols: &C,
+    options: ColOptions,
+) -> Result<SharedIndicators> {
+    bind_cols_with(stmt, options, |binder| FieldBinder::bind(binder, cols))
+}
+
+unsafe fn bind_cols_with<F>(stmt: SQLHSTMT, options: ColOptions, f: F) -> Result<SharedIndicators>
+where
+    F: FnOnce(ColBinder) -> Result<(ColBinder, SharedIndicators)>,
+{
+    let descriptions = if options.validate || options.by_name {
+        describe_cols(stmt)?
+    } else {
+        Vec::new()
+    };
+
+    let (binder, shared) = f(ColBinder {
+        stmt,
+        options,
+        descriptions,
+        bound_cols: Vec::new(),
+    })?;
+
+    if options.validate && binder.descriptions.len() != binder.bound_cols.len() {
+        return Err(Error::Bind(format!(
+            "{} fields are bound but the result has {} columns",
+            binder.bound_cols.len(),
+            binder.descriptions.len()
+        )));
+    }
+
+    Ok(shared)
+}
+
+impl ColBinder {
+    fn next_col(&mut self, field: &FieldPath) -> Result<SQLUSMALLINT> {
+        let col_nr = if self.options.by_name {
+            let name = field.name().unwrap_or_default();
+
+            let pos = self
+                .descriptions
+                .iter()
+                .position(|col| col.name.eq_ignore_ascii_case(name))
+                .ok_or_else(|| Error::Bind(format!("field {} does not match any column", field)))?;
+
+            let col_nr = (pos + 1) as SQLUSMALLINT;
+
+            if self.bound_cols.contains(&col_nr) {
+                return Err(Error::Bind(format!(
+                    "field {} matches column {} ({}) which is already bound",
+                    field, col_nr, self.descriptions[pos].name
+                )));
+            }
+
+            col_nr
+        } else {
+            (self.bound_cols.len() + 1) as SQLUSMALLINT
+        };
+
+        self.bound_cols.push(col_nr);
+
+        Ok(col_nr)
+    }
+
+    fn validate(
+        &self,
+        field: &FieldPath,
+        col_nr: SQLUSMALLINT,
+        c_data_type: SqlCDataType,
+        nullable: bool,
+    ) -> Result<()> {
+        if !self.options.validate {
+            return Ok(());
+        }
+
+        let col = self.descriptions.get(col_nr as usize - 1).ok_or_else(|| {
+            Error::Bind(format!(
+                "field {} is bound to column {} but the result has only {} columns",
+                field,
+                col_nr,
+                self.descriptions.len()
+            ))
+        })?;
+
+        if !nullable && col.nullable == Some(true) {
+            return Err(Error::Bind(format!(
+                "field {} is not nullable but column {} ({}) is",
+                field, col_nr, col.name
+            )));
+        }
+
+        if !is_compatible(c_data_type, col.data_type) {
+            return Err(Error::Bind(format!(
+                "field {} of type {:?} cannot hold column {} ({}) of type {:?}",
+                field, c_data_type, col_nr, col.name, col.data_type
+            )));
+        }
+
+        Ok(())
+    }
 }
 
 impl BinderImpl for ColBinder {
-    fn bind<T: BindTypes>(
+    fn bind(
         &mut self,
+        field: &FieldPath,
+        bind_type: BindType,
         value_ptr: SQLPOINTER,
         indicator_ptr: *mut SQLLEN,
     ) -> Result<()> {
-        self.col_nr += 1;
+        let col_nr = self.next_col(field)?;
+
+        self.validate(
+            field,
+            col_nr,
+            bind_type.c_data_type,
+            !indicator_ptr.is_null(),
+        )?;
 
         unsafe {
             SQLBindCol(
                 self.stmt,
-                self.col_nr,
-                T::c_data_type(),
+                col_nr,
+                bind_type.c_data_type,
                 value_ptr,
-                size_of::<T>() as SQLLEN,
+                bind_type.size as SQLLEN,
                 indicator_ptr,
             )
         }
@@ -55,16 +180,20 @@ impl BinderImpl for ColBinder {
 
     fn bind_str(
         &mut self,
+        field: &FieldPath,
         length: usize,
         value_ptr: SQLPOINTER,
         indicator_ptr: *mut SQLLEN,
+        nullable: bool,
     ) -> Result<()> {
-        self.col_nr += 1;
+        let col_nr = self.next_col(field)?;
+
+        self.validate(field, col_nr, SQL_C_CHAR, nullable)?;
 
         unsafe {
             SQLBindCol(
                 self.stmt,
-                self.col_nr,
+                col_nr,
                 SQL_C_CHAR,
                 value_ptr,
                 (length + 1) as SQLLEN,
@@ -73,4 +202,225 @@ impl BinderImpl for ColBinder {
         }
         .check()
     }
+
+    fn bind_null(&mut self, field: &FieldPath) -> Result<()> {
+        self.value_dependent(field)
+    }
+
+    fn value_dependent(&mut self, field: &FieldPath) -> Result<()> {
+        Err(Error::Bind(format!(
+            "field {} is an Option which cannot be bound to a column, use Nullable instead",
+            field
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use generic_array::typenum::U8;
+    use serde::Serialize;
+
+    use crate::{
+        col_binding::{Cols, NoCols},
+        connection::{Connection, Environment},
+        error::Error,
+        nullable::Nullable,
+        param_binding::{NoParams, Params},
+        statement::Statement,
+        string::String,
+        tests::CONN_STR,
+    };
+
+    #[test]
+    fn validate_cols() {
+        let env = Environment::new().unwrap();
+        let conn = Connection::new(&env, CONN_STR).unwrap();
+
+        {
+            let mut stmt: Statement<NoParams, NoCols> = Statement::new(
+                &conn,
+                "CREATE TEMPORARY TABLE tbl (id INTEGER NOT NULL, text VARCHAR(8) NOT NULL)",
+            )
+            .unwrap();
+            stmt.exec().unwrap();
+        }
+
+        {
+            let mut stmt: Statement<NoParams, Cols<(i32, String<U8>)>> =
+                Statement::new(&conn, "SELECT id, text FROM tbl").unwrap();
+            stmt.set_validate_cols(true);
+            stmt.exec().unwrap();
+        }
+
+        {
+            let mut stmt: Statement<NoParams, Cols<i32>> =
+                Statement::new(&conn, "SELECT id, text FROM tbl").unwrap();
+            stmt.set_validate_cols(true);
+            match stmt.exec() {
+                Err(Error::Bind(_)) => (),
+                _ => panic!("missing field not detected"),
+            }
+        }
+
+        {
+            let mut stmt: Statement<NoParams, Cols<(i32, i32)>> =
+                Statement::new(&conn, "SELECT id, text FROM tbl").unwrap();
+            stmt.set_validate_cols(true);
+            match stmt.exec() {
+                Err(Error::Bind(msg)) => assert!(msg.contains("field 1 ")),
+                _ => panic!("type mismatch not detected"),
+            }
+        }
+    }
+
+    #[test]
+    fn validate_nullable_text() {
+        type Row = (i32, Nullable<String<U8>>);
+
+        let env = Environment::new().unwrap();
+        let conn = Connection::new(&env, CONN_STR).unwrap();
+
+        {
+            let mut stmt: Statement<NoParams, NoCols> = Statement::new(
+                &conn,
+                "CREATE TEMPORARY TABLE tbl (id INTEGER NOT NULL, text VARCHAR(8))",
+            )
+            .unwrap();
+            stmt.exec().unwrap();
+        }
+
+        {
+            let mut stmt: Statement<Params<Row>, NoCols> =
+                Statement::new(&conn, "INSERT INTO tbl (id, text) VALUES (?, ?)").unwrap();
+
+            let mut text = String::<U8>::default();
+            text.extend_from_slice(b"foo");
+
+            *stmt.params() = (1, Some(text).into());
+            stmt.exec().unwrap();
+
+            *stmt.params() = (2, None.into());
+            stmt.exec().unwrap();
+        }
+
+        {
+            let mut stmt: Statement<NoParams, Cols<Row>> =
+                Statement::new(&conn, "SELECT id, text FROM tbl ORDER BY id").unwrap();
+            stmt.set_validate_cols(true);
+            stmt.exec().unwrap();
+            assert!(stmt.fetch().unwrap());
+            assert_eq!(1, stmt.cols().0);
+            assert_eq!(
+                Some(&b"foo"[..]),
+                stmt.cols().1.as_ref().map(String::as_slice)
+            );
+            assert!(stmt.fetch().unwrap());
+            assert_eq!(2, stmt.cols().0);
+            assert!(stmt.cols().1.as_ref().is_none());
+            assert!(!stmt.fetch().unwrap());
+        }
+
+        {
+            let mut stmt: Statement<NoParams, Cols<(i32, String<U8>)>> =
+                Statement::new(&conn, "SELECT id, text FROM tbl").unwrap();
+            stmt.set_validate_cols(true);
+            match stmt.exec() {
+                Err(Error::Bind(msg)) => assert!(msg.contains("field 1 ")),
+                _ => panic!("nullable column not detected"),
+            }
+        }
+    }
+
+    #[test]
+    fn bind_cols_by_name() {
+        #[derive(Clone, Copy, Default, Serialize)]
+        struct Row {
+            text: String<U8>,
+            #[serde(rename = "ID")]
+            key: i32,
+        }
+
+        let env = Environment::new().unwrap();
+        let conn = Connection::new(&env, CONN_STR).unwrap();
+
+        {
+            let mut stmt: Statement<NoParams, NoCols> = Statement::new(
+                &conn,
+                "CREATE TEMPORARY TABLE tbl (id INTEGER NOT NULL, text VARCHAR(8) NOT NULL)",
+            )
+            .unwrap();
+            stmt.exec().unwrap();
+        }
+
+        {
+            let mut stmt: Statement<Params<(i32, String<U8>)>, NoCols> =
+                Statement::new(&conn, "INSERT INTO tbl (id, text) VALUES (?, ?)").unwrap();
+            stmt.params().0 = 42;
+            stmt.params().1.extend_from_slice(b"foobar");
+            stmt.exec().unwrap();
+        }
+
+        {
+            let mut stmt: Statement<NoParams, Cols<Row>> =
+                Statement::new(&conn, "SELECT id, text FROM tbl").unwrap();
+            stmt.set_bind_cols_by_name(true);
+            stmt.exec().unwrap();
+            assert!(stmt.fetch().unwrap());
+            assert_eq!(42, stmt.cols().key);
+            assert_eq!(&b"foobar"[..], stmt.cols().text.as_slice());
+            assert!(!stmt.fetch().unwrap());
+        }
+
+        {
+            let mut stmt: Statement<NoParams, Cols<Row>> =
+                Statement::new(&conn, "SELECT id, text AS label FROM tbl").unwrap();
+            stmt.set_bind_cols_by_name(true);
+            match stmt.exec() {
+                Err(Error::Bind(msg)) => assert!(msg.contains("field text ")),
+                _ => panic!("unmatched field not detected"),
+            }
+        }
+    }
+
+    #[test]
+    fn bind_skipped_and_flattened_cols() {
+        #[derive(Clone, Copy, Default, Serialize)]
+        struct Details {
+            text: String<U8>,
+            #[serde(rename = "amount")]
+            value: i32,
+        }
+
+        #[derive(Clone, Copy, Default, Serialize)]
+        struct Row {
+            id: i32,
+            #[serde(skip)]
+            cached: i32,
+            #[serde(flatten)]
+            details: Details,
+        }
+
+        let env = Environment::new().unwrap();
+        let conn = Connection::new(&env, CONN_STR).unwrap();
+
+        {
+            let mut stmt: Statement<NoParams, Cols<Row>> =
+                Statement::new(&conn, "SELECT 1, 'foo', 2").unwrap();
+            let row = stmt.query_one(()).unwrap();
+            assert_eq!(1, row.id);
+            assert_eq!(0, row.cached);
+            assert_eq!(&b"foo"[..], row.details.text.as_slice());
+            assert_eq!(2, row.details.value);
+        }
+
+        {
+            let mut stmt: Statement<NoParams, Cols<Row>> =
+                Statement::new(&conn, "SELECT 2 AS amount, 'bar' AS text, 3 AS id").unwrap();
+            stmt.set_bind_cols_by_name(true);
+            let row = stmt.query_one(()).unwrap();
+            assert_eq!(3, row.id);
+            assert_eq!(&b"bar"[..], row.details.text.as_slice());
+            assert_eq!(2, row.details.value);
+        }
+    }
 }
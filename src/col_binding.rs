@@ -14,17 +14,20 @@ GNU Lesser General Public License for more details.
 You should have received a copy of the GNU Lesser General Public License
 along with serde-odbc.  If not, see <http://www.gnu.org/licenses/>.
 */
+use std::cmp::min;
+use std::marker::PhantomData;
 use std::mem::size_of;
 use std::ptr::null;
+use std::slice::{from_mut, from_ref};
 
+use super::bindable::{BindWith, Serde};
+use super::binder::SharedIndicators;
+use super::col_binder::ColOptions;
+use super::error::{Error, OdbcResult, Result};
 use odbc_sys::{
     SQLSetStmtAttr, SQLHSTMT, SQLLEN, SQLPOINTER, SQL_ATTR_ROWS_FETCHED_PTR,
     SQL_ATTR_ROW_ARRAY_SIZE, SQL_ATTR_ROW_BIND_TYPE,
 };
-use serde::ser::Serialize;
-
-use super::col_binder::bind_cols;
-use super::error::{OdbcResult, Result};
 
 pub trait ColBinding {
     fn new() -> Self;
@@ -32,32 +35,56 @@ pub trait ColBinding {
     type Cols;
     fn cols(&self) -> &Self::Cols;
 
-    unsafe fn bind(&mut self, stmt: SQLHSTMT) -> Result<()>;
+    type Row;
+    fn rows(&self) -> &[Self::Row];
+    fn rows_mut(&mut self) -> &mut [Self::Row];
+
+    fn truncate(&mut self, len: usize);
+
+    fn row_array_size(&self) -> usize;
+
+    unsafe fn bind(&mut self, stmt: SQLHSTMT, options: ColOptions) -> Result<()>;
+
+    fn fetch(&mut self) -> Result<bool>;
+
+    // Writes the lengths of the rows into the indicators bound with them,
+    // e.g. before passing them to `SQLSetPos`.
+    fn flush(&mut self);
+
+    // Copies as many of `rows` as the bound buffers hold into them,
+    // e.g. to add them via `SQLBulkOperations`, and returns their number.
+    fn stage(&mut self, rows: &[Self::Row]) -> Result<usize>;
 
-    fn fetch(&mut self) -> bool;
+    fn invalidate(&mut self);
 }
 
-pub struct Cols<C: Copy + Default + Serialize> {
+pub struct Cols<C: Copy + Default, B: BindWith<C> = Serde> {
     data: C,
     last_data: *const C,
+    shared: SharedIndicators,
+    _binding: PhantomData<B>,
 }
 
 pub struct NoCols {
     data: (),
 }
 
-pub struct RowSet<C: Copy + Default + Serialize> {
+pub struct RowSet<C: Copy + Default, B: BindWith<C> = Serde> {
     data: Vec<C>,
     last_data: *const C,
     last_size: usize,
     rows_fetched: SQLLEN,
+    shared: SharedIndicators,
+    _binding: PhantomData<B>,
 }
 
-impl<C: Copy + Default + Serialize> ColBinding for Cols<C> {
+impl<C: Copy + Default, B: BindWith<C>> ColBinding for Cols<C, B> {
     fn new() -> Self {
         Cols {
             data: Default::default(),
             last_data: null(),
+            shared: Default::default(),
+            _binding: PhantomData,
         }
     }
 
@@ -66,19 +93,52 @@ impl<C: Copy + Default + Serialize> ColBinding for Cols<C> {
         &self.data
     }
 
-    unsafe fn bind(&mut self, stmt: SQLHSTMT) -> Result<()> {
+    type Row = C;
+    fn rows(&self) -> &[Self::Row] {
+        from_ref(&self.data)
+    }
+    fn rows_mut(&mut self) -> &mut [Self::Row] {
+        from_mut(&mut self.data)
+    }
+
+    fn truncate(&mut self, _len: usize) {}
+
+    fn row_array_size(&self) -> usize {
+        1
+    }
+
+    unsafe fn bind(&mut self, stmt: SQLHSTMT, options: ColOptions) -> Result<()> {
         let data = &self.data as *const C;
 
         if self.last_data != data {
-            bind_cols(stmt, &*data)?;
+            self.shared = B::bind_cols(stmt, &*data, options)?;
             self.last_data = data;
         }
 
         Ok(())
     }
 
-    fn fetch(&mut self) -> bool {
-        true
+    fn fetch(&mut self) -> Result<bool> {
+        unsafe { self.shared.read(&mut self.data as *mut C as *mut u8) };
+        Ok(true)
+    }
+
+    fn flush(&mut self) {
+        unsafe { self.shared.write(&mut self.data as *mut C as *mut u8) };
+    }
+
+    fn stage(&mut self, rows: &[Self::Row]) -> Result<usize> {
+        if self.last_data.is_null() {
+            return Err(not_bound());
+        }
+
+        self.data = rows[0];
+        self.flush();
+        Ok(1)
+    }
+
+    fn invalidate(&mut self) {
+        self.last_data = null();
     }
 }
 
@@ -92,22 +152,46 @@ impl ColBinding for NoCols {
         &self.data
     }
 
-    unsafe fn bind(&mut self, _stmt: SQLHSTMT) -> Result<()> {
+    type Row = ();
+    fn rows(&self) -> &[Self::Row] {
+        from_ref(&self.data)
+    }
+    fn rows_mut(&mut self) -> &mut [Self::Row] {
+        from_mut(&mut self.data)
+    }
+
+    fn truncate(&mut self, _len: usize) {}
+
+    fn row_array_size(&self) -> usize {
+        1
+    }
+
+    unsafe fn bind(&mut self, _stmt: SQLHSTMT, _options: ColOptions) -> Result<()> {
         Ok(())
     }
 
-    fn fetch(&mut self) -> bool {
-        true
+    fn fetch(&mut self) -> Result<bool> {
+        Ok(true)
     }
+
+    fn flush(&mut self) {}
+
+    fn stage(&mut self, _rows: &[Self::Row]) -> Result<usize> {
+        Err(not_bound())
+    }
+
+    fn invalidate(&mut self) {}
 }
 
-impl<C: Copy + Default + Serialize> ColBinding for RowSet<C> {
+impl<C: Copy + Default, B: BindWith<C>> ColBinding for RowSet<C, B> {
     fn new() -> Self {
         RowSet {
             data: Vec::new(),
             last_data: null(),
             last_size: 0,
             rows_fetched: 0,
+            shared: Default::default(),
+            _binding: PhantomData,
         }
     }
 
@@ -116,7 +200,23 @@ impl<C: Copy + Default + Serialize> ColBinding for RowSet<C> {
         &self.data
     }
 
-    unsafe fn bind(&mut self, stmt: SQLHSTMT) -> Result<()> {
+    type Row = C;
+    fn rows(&self) -> &[Self::Row] {
+        &self.data
+    }
+    fn rows_mut(&mut self) -> &mut [Self::Row] {
+        &mut self.data
+    }
+
+    fn truncate(&mut self, len: usize) {
+        self.data.truncate(len);
+    }
+
+    fn row_array_size(&self) -> usize {
+        self.last_size
+    }
+
+    unsafe fn bind(&mut self, stmt: SQLHSTMT, options: ColOptions) -> Result<()> {
         let capacity = self.data.capacity();
         self.data.resize(capacity, Default::default());
 
@@ -124,7 +224,7 @@ impl<C: Copy + Default + Serialize> ColBinding for RowSet<C> {
         let size = self.data.len();
 
         if self.last_data != data {
-            bind_cols(stmt, &*data)?;
+            self.shared = B::bind_cols(stmt, &*data, options)?;
             self.last_data = data;
         }
 
@@ -136,13 +236,48 @@ impl<C: Copy + Default + Serialize> ColBinding for RowSet<C> {
         Ok(())
     }
 
-    fn fetch(&mut self) -> bool {
-        self.data.truncate(self.rows_fetched as usize);
-        self.rows_fetched != 0
+    fn fetch(&mut self) -> Result<bool> {
+        // `bind` initialized the whole capacity which the driver fills with
+        // up to `SQL_ATTR_ROW_ARRAY_SIZE` rows, even after a shorter block.
+        unsafe { self.data.set_len(self.rows_fetched as usize) };
+        for row in &mut self.data {
+            unsafe { self.shared.read(row as *mut C as *mut u8) };
+        }
+        Ok(self.rows_fetched != 0)
+    }
+
+    fn flush(&mut self) {
+        for row in &mut self.data {
+            unsafe { self.shared.write(row as *mut C as *mut u8) };
+        }
     }
+
+    fn stage(&mut self, rows: &[Self::Row]) -> Result<usize> {
+        if self.last_data.is_null() {
+            return Err(not_bound());
+        }
+
+        // Stays within the capacity so that the bound buffers are not moved.
+        let len = min(rows.len(), self.last_size);
+
+        self.data.clear();
+        self.data.extend_from_slice(&rows[..len]);
+        self.flush();
+
+        Ok(len)
+    }
+
+    fn invalidate(&mut self) {
+        self.last_data = null();
+        self.last_size = 0;
+    }
+}
+
+pub(crate) fn not_bound() -> Error {
+    Error::Bind("rows can only be staged into columns bound by executing a query".to_owned())
 }
 
-impl<C: Copy + Default + Serialize> RowSet<C> {
+impl<C: Copy + Default, B: BindWith<C>> RowSet<C, B> {
     pub fn fetch_size(&self) -> usize {
         self.data.capacity()
     }
@@ -14,6 +14,7 @@ GNU Lesser General Public License for more details.
 You should have received a copy of the GNU Lesser General Public License
 along with serde-odbc.  If not, see <http://www.gnu.org/licenses/>.
 */
+use std::cell::{RefCell, RefMut};
 use std::ptr::null_mut;
 
 use odbc_sys::{
@@ -23,7 +24,11 @@ use odbc_sys::{
     SQL_HANDLE_DBC, SQL_HANDLE_ENV, SQL_OV_ODBC3, SQL_ROLLBACK,
 };
 
-use crate::error::{OdbcResult, Result};
+use crate::cache::{CachedStatement, StatementCache, DEFAULT_CAPACITY};
+use crate::col_binding::ColBinding;
+use crate::error::{Error, OdbcResult, Result};
+use crate::param_binding::ParamBinding;
+use crate::statement::Statement;
 
 pub struct Environment(SQLHENV);
 
@@ -52,7 +57,10 @@ impl Drop for Environment {
     }
 }
 
-pub struct Connection(SQLHDBC);
+pub struct Connection {
+    dbc: SQLHDBC,
+    cache: RefCell<StatementCache>,
+}
 
 impl Connection {
     pub fn new(env: &Environment, conn_str: &str) -> Result<Self> {
@@ -78,20 +86,66 @@ impl Connection {
 
         unsafe { SQLSetConnectAttr(dbc, SQL_ATTR_AUTOCOMMIT, null_mut(), 0) }.check()?;
 
-        Ok(Connection(dbc))
+        Ok(Connection {
+            dbc,
+            cache: RefCell::new(StatementCache::new(DEFAULT_CAPACITY)),
+        })
     }
 
     pub fn handle(&self) -> SQLHANDLE {
-        self.0 as SQLHANDLE
+        self.dbc as SQLHANDLE
     }
 
     pub fn begin(&self) -> Transaction {
         Transaction(Some(self))
     }
+
+    pub fn prepare_cached<P, C>(&self, stmt_str: &str) -> Result<CachedStatement<'_, P, C>>
+    where
+        P: ParamBinding + 'static,
+        C: ColBinding + 'static,
+    {
+        let cached = self.statement_cache().take(stmt_str);
+
+        let stmt = match cached {
+            Some(stmt) => match stmt.downcast::<Statement<P, C>>() {
+                Ok(stmt) => *stmt,
+                Err(stmt) => {
+                    self.statement_cache().put(stmt_str.to_owned(), stmt);
+
+                    return Err(Error::Bind(format!(
+                        "statement {:?} is cached with different parameter or column types",
+                        stmt_str
+                    )));
+                }
+            },
+            None => Statement::new(self, stmt_str)?,
+        };
+
+        Ok(CachedStatement::new(self, stmt_str.to_owned(), stmt))
+    }
+
+    pub fn statement_cache_capacity(&self) -> usize {
+        self.statement_cache().capacity()
+    }
+
+    pub fn set_statement_cache_capacity(&self, capacity: usize) {
+        self.statement_cache().set_capacity(capacity);
+    }
+
+    pub fn clear_statement_cache(&self) {
+        self.statement_cache().clear();
+    }
+
+    pub(crate) fn statement_cache(&self) -> RefMut<'_, StatementCache> {
+        self.cache.borrow_mut()
+    }
 }
 
 impl Drop for Connection {
     fn drop(&mut self) {
+        self.cache.get_mut().clear();
+
         let _ = unsafe { SQLFreeHandle(SQL_HANDLE_DBC, self.handle()) };
     }
 }
@@ -0,0 +1,313 @@
+/*
+This file is part of serde-odbc.
+
+serde-odbc is free software: you can redistribute it and/or modify
+it under the terms of the GNU Lesser General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+serde-odbc is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Lesser General Public License for more details.
+
+You should have received a copy of the GNU Lesser General Public License
+along with serde-odbc.  If not, see <http://www.gnu.org/licenses/>.
+*/
+use odbc_sys::{SQLINTEGER, SQLULEN};
+
+use crate::ffi::{
+    SQL_ATTR_CONCURRENCY, SQL_ATTR_CURSOR_TYPE, SQL_CONCUR_LOCK, SQL_CONCUR_READ_ONLY,
+    SQL_CONCUR_ROWVER, SQL_CONCUR_VALUES, SQL_CURSOR_DYNAMIC, SQL_CURSOR_FORWARD_ONLY,
+    SQL_CURSOR_KEYSET_DRIVEN, SQL_CURSOR_STATIC,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CursorType {
+    ForwardOnly,
+    Static,
+    KeysetDriven,
+    Dynamic,
+}
+
+impl CursorType {
+    pub(crate) fn is_scrollable(self) -> bool {
+        self != CursorType::ForwardOnly
+    }
+
+    pub(crate) fn attr(self) -> (SQLINTEGER, SQLULEN) {
+        let value = match self {
+            CursorType::ForwardOnly => SQL_CURSOR_FORWARD_ONLY,
+            CursorType::Static => SQL_CURSOR_STATIC,
+            CursorType::KeysetDriven => SQL_CURSOR_KEYSET_DRIVEN,
+            CursorType::Dynamic => SQL_CURSOR_DYNAMIC,
+        };
+
+        (SQL_ATTR_CURSOR_TYPE, value)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Concurrency {
+    ReadOnly,
+    Lock,
+    RowVersion,
+    Values,
+}
+
+impl Concurrency {
+    pub(crate) fn attr(self) -> (SQLINTEGER, SQLULEN) {
+        let value = match self {
+            Concurrency::ReadOnly => SQL_CONCUR_READ_ONLY,
+            Concurrency::Lock => SQL_CONCUR_LOCK,
+            Concurrency::RowVersion => SQL_CONCUR_ROWVER,
+            Concurrency::Values => SQL_CONCUR_VALUES,
+        };
+
+        (SQL_ATTR_CONCURRENCY, value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use generic_array::typenum::U8;
+
+    use crate::{
+        col_binding::{Cols, NoCols, RowSet},
+        connection::{Connection, Environment},
+        error::Error,
+        nullable::Nullable,
+        param_binding::{NoParams, ParamSet},
+        statement::Statement,
+        string::String,
+        tests::CONN_STR,
+    };
+
+    #[test]
+    fn scroll_cursor() {
+        let env = Environment::new().unwrap();
+        let conn = Connection::new(&env, CONN_STR).unwrap();
+
+        {
+            let mut stmt: Statement<NoParams, NoCols> =
+                Statement::new(&conn, "CREATE TEMPORARY TABLE tbl (col INTEGER NOT NULL)").unwrap();
+            stmt.exec().unwrap();
+        }
+
+        {
+            let mut stmt: Statement<ParamSet<i32>, NoCols> =
+                Statement::new(&conn, "INSERT INTO tbl (col) VALUES (?)").unwrap();
+            stmt.params().extend(0..10);
+            stmt.exec().unwrap();
+        }
+
+        {
+            let mut stmt: Statement<NoParams, Cols<i32>> = Statement::with_cursor_type(
+                &conn,
+                "SELECT col FROM tbl ORDER BY col",
+                CursorType::Static,
+            )
+            .unwrap();
+            stmt.exec().unwrap();
+            assert!(stmt.fetch_last().unwrap());
+            assert_eq!(9, *stmt.cols());
+            assert!(stmt.fetch_first().unwrap());
+            assert_eq!(0, *stmt.cols());
+            assert!(stmt.fetch_absolute(5).unwrap());
+            assert_eq!(4, *stmt.cols());
+            assert!(stmt.fetch_relative(2).unwrap());
+            assert_eq!(6, *stmt.cols());
+            assert!(stmt.fetch_prior().unwrap());
+            assert_eq!(5, *stmt.cols());
+            assert!(!stmt.fetch_absolute(11).unwrap());
+        }
+
+        {
+            let mut stmt: Statement<NoParams, Cols<i32>> = Statement::with_cursor_type(
+                &conn,
+                "SELECT col FROM tbl ORDER BY col",
+                CursorType::Static,
+            )
+            .unwrap();
+            stmt.set_max_rows(Some(3)).unwrap();
+            stmt.exec().unwrap();
+            for i in 0..3 {
+                assert!(stmt.fetch().unwrap());
+                assert_eq!(i, *stmt.cols());
+            }
+            assert!(!stmt.fetch().unwrap());
+            assert!(stmt.fetch_first().unwrap());
+            assert_eq!(0, *stmt.cols());
+            assert!(stmt.fetch().unwrap());
+            assert_eq!(1, *stmt.cols());
+            assert!(stmt.fetch_absolute(3).unwrap());
+            assert_eq!(2, *stmt.cols());
+            assert!(!stmt.fetch_absolute(5).unwrap());
+            assert!(stmt.fetch_last().unwrap());
+            assert_eq!(2, *stmt.cols());
+        }
+
+        {
+            let mut stmt: Statement<NoParams, RowSet<i32>> = Statement::with_cursor_type(
+                &conn,
+                "SELECT col FROM tbl ORDER BY col",
+                CursorType::Static,
+            )
+            .unwrap();
+            stmt.set_fetch_size(4);
+            stmt.exec().unwrap();
+            assert!(stmt.fetch_absolute(9).unwrap());
+            assert_eq!(&[8, 9][..], &stmt.cols()[..]);
+            assert!(stmt.fetch_first().unwrap());
+            assert_eq!(&[0, 1, 2, 3][..], &stmt.cols()[..]);
+        }
+    }
+
+    #[test]
+    fn update_and_delete_rows() {
+        let env = Environment::new().unwrap();
+        let conn = Connection::new(&env, CONN_STR).unwrap();
+
+        {
+            let mut stmt: Statement<NoParams, NoCols> =
+                Statement::new(&conn, "CREATE TEMPORARY TABLE tbl (col INTEGER NOT NULL)").unwrap();
+            stmt.exec().unwrap();
+        }
+
+        {
+            let mut stmt: Statement<ParamSet<i32>, NoCols> =
+                Statement::new(&conn, "INSERT INTO tbl (col) VALUES (?)").unwrap();
+            stmt.params().extend(0..4);
+            stmt.exec().unwrap();
+        }
+
+        {
+            let mut stmt: Statement<NoParams, RowSet<i32>> = Statement::with_cursor(
+                &conn,
+                "SELECT col FROM tbl ORDER BY col",
+                CursorType::Static,
+                Concurrency::Lock,
+            )
+            .unwrap();
+            stmt.set_fetch_size(4);
+            stmt.exec().unwrap();
+            assert!(stmt.fetch().unwrap());
+            stmt.rows_mut()[1] = 42;
+            stmt.update_row(1).unwrap();
+            stmt.delete_row(2).unwrap();
+        }
+
+        {
+            let mut stmt: Statement<NoParams, RowSet<i32>> =
+                Statement::with_fetch_size(&conn, "SELECT col FROM tbl ORDER BY col", 4).unwrap();
+            stmt.exec().unwrap();
+            assert!(stmt.fetch().unwrap());
+            assert_eq!(&[0, 3, 42][..], &stmt.cols()[..]);
+        }
+    }
+
+    #[test]
+    fn update_nullable_str() {
+        let env = Environment::new().unwrap();
+        let conn = Connection::new(&env, CONN_STR).unwrap();
+
+        {
+            let mut stmt: Statement<NoParams, NoCols> = Statement::new(
+                &conn,
+                "CREATE TEMPORARY TABLE tbl (id INTEGER NOT NULL, col TEXT)",
+            )
+            .unwrap();
+            stmt.exec().unwrap();
+        }
+
+        {
+            let mut stmt: Statement<ParamSet<i32>, NoCols> =
+                Statement::new(&conn, "INSERT INTO tbl (id, col) VALUES (?, 'foo')").unwrap();
+            stmt.params().extend(0..2);
+            stmt.exec().unwrap();
+        }
+
+        type Row = (i32, Nullable<String<U8>>);
+
+        {
+            let mut stmt: Statement<NoParams, RowSet<Row>> = Statement::with_cursor(
+                &conn,
+                "SELECT id, col FROM tbl ORDER BY id",
+                CursorType::Static,
+                Concurrency::Lock,
+            )
+            .unwrap();
+            stmt.set_fetch_size(2);
+            stmt.exec().unwrap();
+            assert!(stmt.fetch().unwrap());
+
+            // The changed length is written into the indicator bound with it.
+            let col = stmt.rows_mut()[0].1.as_mut().unwrap();
+            col.clear();
+            col.extend_from_slice(b"foobar");
+            stmt.update_row(0).unwrap();
+
+            stmt.rows_mut()[1].1 = Nullable::from(None);
+            stmt.update_row(1).unwrap();
+        }
+
+        {
+            let mut stmt: Statement<NoParams, RowSet<Row>> =
+                Statement::with_fetch_size(&conn, "SELECT id, col FROM tbl ORDER BY id", 2)
+                    .unwrap();
+            stmt.exec().unwrap();
+            assert!(stmt.fetch().unwrap());
+            assert_eq!(
+                &b"foobar"[..],
+                stmt.cols()[0].1.as_ref().unwrap().as_slice()
+            );
+            assert!(stmt.cols()[1].1.as_ref().is_none());
+        }
+    }
+
+    #[test]
+    fn add_rows() {
+        let env = Environment::new().unwrap();
+        let conn = Connection::new(&env, CONN_STR).unwrap();
+
+        {
+            let mut stmt: Statement<NoParams, NoCols> =
+                Statement::new(&conn, "CREATE TEMPORARY TABLE tbl (col INTEGER NOT NULL)").unwrap();
+            stmt.exec().unwrap();
+        }
+
+        {
+            let mut stmt: Statement<NoParams, RowSet<i32>> = Statement::with_cursor(
+                &conn,
+                "SELECT col FROM tbl ORDER BY col",
+                CursorType::Static,
+                Concurrency::Lock,
+            )
+            .unwrap();
+            stmt.set_fetch_size(4);
+            stmt.exec().unwrap();
+            stmt.add_rows(&[0, 1, 2, 3, 4, 5]).unwrap();
+            assert_eq!(&[4, 5][..], &stmt.cols()[..]);
+        }
+
+        {
+            let mut stmt: Statement<NoParams, RowSet<i32>> =
+                Statement::with_fetch_size(&conn, "SELECT col FROM tbl ORDER BY col", 8).unwrap();
+            stmt.exec().unwrap();
+            assert!(stmt.fetch().unwrap());
+            assert_eq!(&[0, 1, 2, 3, 4, 5][..], &stmt.cols()[..]);
+            assert!(!stmt.fetch().unwrap());
+        }
+
+        {
+            let mut stmt: Statement<NoParams, RowSet<i32>> =
+                Statement::with_fetch_size(&conn, "SELECT col FROM tbl", 4).unwrap();
+            match stmt.add_rows(&[6]) {
+                Err(Error::Bind(_)) => (),
+                _ => panic!("staging into unbound columns not rejected"),
+            }
+        }
+    }
+}
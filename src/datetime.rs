@@ -0,0 +1,403 @@
+/*
+This file is part of serde-odbc.
+
+serde-odbc is free software: you can redistribute it and/or modify
+it under the terms of the GNU Lesser General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+serde-odbc is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Lesser General Public License for more details.
+
+You should have received a copy of the GNU Lesser General Public License
+along with serde-odbc.  If not, see <http://www.gnu.org/licenses/>.
+*/
+use std::fmt;
+
+use odbc_sys::{SQL_DATE_STRUCT, SQL_TIMESTAMP_STRUCT, SQL_TIME_STRUCT};
+use serde::de::{self, Deserialize, Deserializer, Unexpected};
+use serde::ser::{Serialize, Serializer};
+
+use crate::bind_types::BindType;
+use crate::binder::with_bind_type;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(C)]
+pub struct Date {
+    pub year: i16,
+    pub month: u16,
+    pub day: u16,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(C)]
+pub struct Time {
+    pub hour: u16,
+    pub minute: u16,
+    pub second: u16,
+}
+
+// `fraction` is given in nanoseconds.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(C)]
+pub struct Timestamp {
+    pub year: i16,
+    pub month: u16,
+    pub day: u16,
+    pub hour: u16,
+    pub minute: u16,
+    pub second: u16,
+    pub fraction: u32,
+}
+
+impl Timestamp {
+    pub fn new(date: Date, time: Time, fraction: u32) -> Self {
+        Timestamp {
+            year: date.year,
+            month: date.month,
+            day: date.day,
+            hour: time.hour,
+            minute: time.minute,
+            second: time.second,
+            fraction,
+        }
+    }
+
+    pub fn date(&self) -> Date {
+        Date {
+            year: self.year,
+            month: self.month,
+            day: self.day,
+        }
+    }
+
+    pub fn time(&self) -> Time {
+        Time {
+            hour: self.hour,
+            minute: self.minute,
+            second: self.second,
+        }
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl fmt::Display for Time {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            fmt,
+            "{:02}:{:02}:{:02}",
+            self.hour, self.minute, self.second
+        )
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{} {}.{:09}", self.date(), self.time(), self.fraction)
+    }
+}
+
+fn parse_date(value: &str) -> Option<Date> {
+    // Splitting from the end keeps the sign of negative years.
+    let mut parts = value.rsplitn(3, '-');
+
+    let day = parts.next()?.parse().ok()?;
+    let month = parts.next()?.parse().ok()?;
+    let year = parts.next()?.parse().ok()?;
+
+    Some(Date { year, month, day })
+}
+
+fn parse_time(value: &str) -> Option<Time> {
+    let mut parts = value.splitn(3, ':');
+
+    let hour = parts.next()?.parse().ok()?;
+    let minute = parts.next()?.parse().ok()?;
+    let second = parts.next()?.parse().ok()?;
+
+    Some(Time {
+        hour,
+        minute,
+        second,
+    })
+}
+
+fn parse_timestamp(value: &str) -> Option<Timestamp> {
+    let (date, time) = value.split_once([' ', 'T'])?;
+
+    let (time, fraction) = match time.split_once('.') {
+        Some((time, digits)) => (time, parse_fraction(digits)?),
+        None => (time, 0),
+    };
+
+    Some(Timestamp::new(
+        parse_date(date)?,
+        parse_time(time)?,
+        fraction,
+    ))
+}
+
+// Scales fractional seconds given with up to nine digits to nanoseconds.
+fn parse_fraction(digits: &str) -> Option<u32> {
+    if digits.is_empty() || digits.len() > 9 || !digits.bytes().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    Some(digits.parse::<u32>().ok()? * 10_u32.pow(9 - digits.len() as u32))
+}
+
+macro_rules! impl_datetime {
+    ($type:ident, $raw:ident { $($field:ident),* }, $parse:ident, $expecting:literal) => {
+        // Bound as a single value, other serializers see the formatted string.
+        impl Serialize for $type {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                with_bind_type(BindType::of::<Self>(), || {
+                    serializer.serialize_newtype_struct(stringify!($type), &self.to_string())
+                })
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $type {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                struct Visitor;
+
+                impl de::Visitor<'_> for Visitor {
+                    type Value = $type;
+
+                    fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+                        fmt.write_str($expecting)
+                    }
+
+                    fn visit_str<E: de::Error>(self, value: &str) -> Result<$type, E> {
+                        $parse(value).ok_or_else(|| E::invalid_value(Unexpected::Str(value), &self))
+                    }
+                }
+
+                deserializer.deserialize_str(Visitor)
+            }
+        }
+
+        impl From<$raw> for $type {
+            fn from(value: $raw) -> Self {
+                $type {
+                    $($field: value.$field,)*
+                }
+            }
+        }
+
+        impl From<$type> for $raw {
+            fn from(value: $type) -> Self {
+                $raw {
+                    $($field: value.$field,)*
+                }
+            }
+        }
+    };
+}
+
+impl_datetime!(
+    Date,
+    SQL_DATE_STRUCT { year, month, day },
+    parse_date,
+    "a date formatted as YYYY-MM-DD"
+);
+impl_datetime!(
+    Time,
+    SQL_TIME_STRUCT {
+        hour,
+        minute,
+        second
+    },
+    parse_time,
+    "a time formatted as HH:MM:SS"
+);
+impl_datetime!(
+    Timestamp,
+    SQL_TIMESTAMP_STRUCT {
+        year,
+        month,
+        day,
+        hour,
+        minute,
+        second,
+        fraction
+    },
+    parse_timestamp,
+    "a timestamp formatted as YYYY-MM-DD HH:MM:SS.FFFFFFFFF"
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde::Deserialize;
+
+    use crate::{
+        col_binding::{Cols, NoCols},
+        connection::{Connection, Environment},
+        dynamic::{OwnedCols, Value},
+        nullable::Nullable,
+        param_binding::{NoParams, Params},
+        statement::Statement,
+        tests::CONN_STR,
+        Offsets,
+    };
+
+    #[test]
+    fn bind_datetime() {
+        let env = Environment::new().unwrap();
+        let conn = Connection::new(&env, CONN_STR).unwrap();
+
+        let ts = Timestamp::new(
+            Date {
+                year: 2020,
+                month: 2,
+                day: 29,
+            },
+            Time {
+                hour: 23,
+                minute: 59,
+                second: 58,
+            },
+            123_000_000,
+        );
+        assert_eq!("2020-02-29 23:59:58.123000000", ts.to_string());
+
+        {
+            let mut stmt: Statement<NoParams, NoCols> = Statement::new(
+                &conn,
+                "CREATE TEMPORARY TABLE tbl (d DATE NOT NULL, t TIME NOT NULL, ts TIMESTAMP)",
+            )
+            .unwrap();
+            stmt.exec().unwrap();
+        }
+
+        {
+            let mut stmt: Statement<Params<(Date, Time, Nullable<Timestamp>)>, NoCols> =
+                Statement::new(&conn, "INSERT INTO tbl (d, t, ts) VALUES (?, ?, ?)").unwrap();
+            *stmt.params() = (ts.date(), ts.time(), Some(ts).into());
+            stmt.exec().unwrap();
+            *stmt.params() = (ts.date(), ts.time(), None.into());
+            stmt.exec().unwrap();
+        }
+
+        {
+            let mut stmt: Statement<NoParams, Cols<(Date, Time, Nullable<Timestamp>)>> =
+                Statement::new(&conn, "SELECT d, t, ts FROM tbl ORDER BY ts DESC").unwrap();
+            let rows = stmt.query_all(()).unwrap();
+            assert_eq!(2, rows.len());
+            assert_eq!(ts.date(), rows[0].0);
+            assert_eq!(ts.time(), rows[0].1);
+            assert_eq!(Some(&ts), rows[0].2.as_ref());
+            assert_eq!(None, rows[1].2.as_ref());
+        }
+
+        {
+            let mut stmt: Statement<NoParams, Cols<Nullable<Timestamp>, Offsets>> =
+                Statement::new(&conn, "SELECT ts FROM tbl WHERE ts IS NOT NULL").unwrap();
+            assert_eq!(Some(&ts), stmt.query_one(()).unwrap().as_ref());
+        }
+    }
+
+    #[test]
+    fn parse_datetime() {
+        assert_eq!(
+            Some(Timestamp::new(
+                Date {
+                    year: 2020,
+                    month: 2,
+                    day: 29,
+                },
+                Time {
+                    hour: 23,
+                    minute: 59,
+                    second: 58,
+                },
+                120_000_000,
+            )),
+            parse_timestamp("2020-02-29 23:59:58.12")
+        );
+        assert_eq!(
+            Some(Date {
+                year: -1,
+                month: 1,
+                day: 2,
+            }),
+            parse_date("-1-01-02")
+        );
+        assert_eq!(None, parse_time("23:59"));
+        assert_eq!(None, parse_timestamp("2020-02-29 23:59:58.1234567890"));
+    }
+
+    #[test]
+    fn decode_datetime() {
+        #[derive(Clone, Debug, PartialEq, Deserialize)]
+        struct Row {
+            d: Date,
+            t: Time,
+            ts: Option<Timestamp>,
+        }
+
+        let env = Environment::new().unwrap();
+        let conn = Connection::new(&env, CONN_STR).unwrap();
+
+        let ts = Timestamp::new(
+            Date {
+                year: 1999,
+                month: 12,
+                day: 31,
+            },
+            Time {
+                hour: 12,
+                minute: 34,
+                second: 56,
+            },
+            0,
+        );
+
+        assert_eq!(Value::Timestamp(ts), Value::from(ts));
+
+        {
+            let mut stmt: Statement<NoParams, NoCols> = Statement::new(
+                &conn,
+                "CREATE TEMPORARY TABLE tbl (d DATE NOT NULL, t TIME NOT NULL, ts TIMESTAMP)",
+            )
+            .unwrap();
+            stmt.exec().unwrap();
+        }
+
+        {
+            let mut stmt: Statement<Params<(Date, Time, Nullable<Timestamp>)>, NoCols> =
+                Statement::new(&conn, "INSERT INTO tbl (d, t, ts) VALUES (?, ?, ?)").unwrap();
+            *stmt.params() = (ts.date(), ts.time(), Some(ts).into());
+            stmt.exec().unwrap();
+            *stmt.params() = (ts.date(), ts.time(), None.into());
+            stmt.exec().unwrap();
+        }
+
+        let mut stmt: Statement<NoParams, OwnedCols<Row>> =
+            Statement::new(&conn, "SELECT d, t, ts FROM tbl ORDER BY ts DESC").unwrap();
+        assert_eq!(
+            vec![
+                Row {
+                    d: ts.date(),
+                    t: ts.time(),
+                    ts: Some(ts),
+                },
+                Row {
+                    d: ts.date(),
+                    t: ts.time(),
+                    ts: None,
+                },
+            ],
+            stmt.query_all(()).unwrap()
+        );
+    }
+}
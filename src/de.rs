@@ -0,0 +1,393 @@
+/*
+This file is part of serde-odbc.
+
+serde-odbc is free software: you can redistribute it and/or modify
+it under the terms of the GNU Lesser General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+serde-odbc is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Lesser General Public License for more details.
+
+You should have received a copy of the GNU Lesser General Public License
+along with serde-odbc.  If not, see <http://www.gnu.org/licenses/>.
+*/
+use std::str::FromStr;
+
+use serde::de::{
+    value::{BorrowedStrDeserializer, SeqDeserializer},
+    DeserializeOwned, DeserializeSeed, Deserializer, Error as _, IntoDeserializer, MapAccess,
+    SeqAccess, Visitor,
+};
+use serde::forward_to_deserialize_any;
+
+use super::dynamic::{Row, Value};
+use super::error::{Error, Result};
+
+impl Row {
+    pub fn decode<T: DeserializeOwned>(&self) -> Result<T> {
+        T::deserialize(RowDeserializer(self))
+    }
+}
+
+struct RowDeserializer<'a>(&'a Row);
+
+impl<'a> RowDeserializer<'a> {
+    fn single_value(&self) -> Result<ValueDeserializer<'a>> {
+        match self.0.values() {
+            [value] => Ok(ValueDeserializer(value)),
+            values => Err(Error::invalid_length(values.len(), &"a single column")),
+        }
+    }
+}
+
+macro_rules! fn_deserialize_single {
+    ($($method:ident)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+                self.single_value()?.$method(visitor)
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for RowDeserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_map(visitor)
+    }
+
+    fn_deserialize_single!(
+        deserialize_bool
+        deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64
+        deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64
+        deserialize_f32 deserialize_f64
+        deserialize_char deserialize_str deserialize_string
+        deserialize_bytes deserialize_byte_buf deserialize_option
+    );
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_seq(ColsAccess {
+            row: self.0,
+            fields: &[],
+            pos: 0,
+        })
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_struct("", &[], visitor)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_map(ColsAccess {
+            row: self.0,
+            fields,
+            pos: 0,
+        })
+    }
+
+    forward_to_deserialize_any! {
+        i128 u128 unit unit_struct enum identifier ignored_any
+    }
+}
+
+struct ColsAccess<'a> {
+    row: &'a Row,
+    fields: &'static [&'static str],
+    pos: usize,
+}
+
+impl<'de> SeqAccess<'de> for ColsAccess<'de> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>> {
+        let value = match self.row.values().get(self.pos) {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+
+        self.pos += 1;
+
+        seed.deserialize(ValueDeserializer(value)).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.row.len() - self.pos)
+    }
+}
+
+impl<'de> MapAccess<'de> for ColsAccess<'de> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        let name = match self.row.names().get(self.pos) {
+            Some(name) => name.as_str(),
+            None => return Ok(None),
+        };
+
+        // Column names are matched case-insensitively like `Cols` bound by name.
+        let name = self
+            .fields
+            .iter()
+            .find(|field| field.eq_ignore_ascii_case(name))
+            .map_or(name, |field| *field);
+
+        seed.deserialize(BorrowedStrDeserializer::new(name))
+            .map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
+        let value = &self.row.values()[self.pos];
+
+        self.pos += 1;
+
+        seed.deserialize(ValueDeserializer(value))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.row.len() - self.pos)
+    }
+}
+
+struct ValueDeserializer<'a>(&'a Value);
+
+impl ValueDeserializer<'_> {
+    fn parse<T: FromStr>(&self, text: &str) -> Result<T> {
+        text.trim()
+            .parse()
+            .map_err(|_| Error::custom(format!("cannot parse {:?} as a number", text)))
+    }
+}
+
+macro_rules! fn_deserialize_int {
+    ($method:ident, $visit:ident) => {
+        fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+            match self.0 {
+                Value::Text(text) | Value::Decimal(text) => visitor.$visit(self.parse(text)?),
+                _ => self.deserialize_any(visitor),
+            }
+        }
+    };
+}
+
+macro_rules! fn_deserialize_float {
+    ($method:ident, $visit:ident) => {
+        fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+            match self.0 {
+                Value::Text(text) | Value::Decimal(text) => visitor.$visit(self.parse(text)?),
+                Value::Integer(value) => visitor.visit_f64(*value as f64),
+                _ => self.deserialize_any(visitor),
+            }
+        }
+    };
+}
+
+impl<'de> Deserializer<'de> for ValueDeserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.0 {
+            Value::Null => visitor.visit_none(),
+            Value::Integer(value) => visitor.visit_i64(*value),
+            Value::Float(value) => visitor.visit_f64(*value),
+            Value::Text(text) | Value::Decimal(text) => visitor.visit_borrowed_str(text),
+            Value::Binary(data) => visitor.visit_borrowed_bytes(data),
+            Value::Date(date) => visitor.visit_string(date.to_string()),
+            Value::Time(time) => visitor.visit_string(time.to_string()),
+            Value::Timestamp(ts) => visitor.visit_string(ts.to_string()),
+        }
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.0 {
+            Value::Integer(value) => visitor.visit_bool(*value != 0),
+            Value::Text(text) | Value::Decimal(text) => {
+                visitor.visit_bool(self.parse::<i64>(text)? != 0)
+            }
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn_deserialize_int!(deserialize_i8, visit_i8);
+    fn_deserialize_int!(deserialize_i16, visit_i16);
+    fn_deserialize_int!(deserialize_i32, visit_i32);
+    fn_deserialize_int!(deserialize_i64, visit_i64);
+    fn_deserialize_int!(deserialize_u8, visit_u8);
+    fn_deserialize_int!(deserialize_u16, visit_u16);
+    fn_deserialize_int!(deserialize_u32, visit_u32);
+    fn_deserialize_int!(deserialize_u64, visit_u64);
+
+    fn_deserialize_float!(deserialize_f32, visit_f32);
+    fn_deserialize_float!(deserialize_f64, visit_f64);
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.0 {
+            Value::Text(text) | Value::Decimal(text) => {
+                visitor.visit_borrowed_bytes(text.as_bytes())
+            }
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        let data = match self.0 {
+            Value::Text(text) | Value::Decimal(text) => text.as_bytes(),
+            Value::Binary(data) => data.as_slice(),
+            _ => return self.deserialize_any(visitor),
+        };
+
+        let mut seq = SeqDeserializer::new(data.iter().copied());
+        let value = visitor.visit_seq(&mut seq)?;
+        seq.end()?;
+
+        Ok(value)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.0 {
+            Value::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        match self.0 {
+            Value::Text(text) => visitor.visit_enum(text.as_str().into_deserializer()),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        i128 u128 char str string unit unit_struct tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use crate::{
+        col_binding::NoCols,
+        connection::{Connection, Environment},
+        dynamic::OwnedCols,
+        param_binding::{NoParams, Params},
+        statement::Statement,
+        tests::CONN_STR,
+    };
+
+    #[test]
+    fn decode_owned_rows() {
+        #[derive(Clone, Debug, PartialEq, Deserialize)]
+        struct Todo {
+            id: i32,
+            text: String,
+            done: bool,
+            note: Option<String>,
+        }
+
+        let env = Environment::new().unwrap();
+        let conn = Connection::new(&env, CONN_STR).unwrap();
+
+        {
+            let mut stmt: Statement<NoParams, NoCols> = Statement::new(
+                &conn,
+                "CREATE TEMPORARY TABLE tbl (id INTEGER NOT NULL, text VARCHAR(4096) NOT NULL, done TINYINT NOT NULL, note VARCHAR(16))",
+            )
+            .unwrap();
+            stmt.exec().unwrap();
+        }
+
+        {
+            let mut stmt: Statement<NoParams, NoCols> = Statement::new(
+                &conn,
+                "INSERT INTO tbl (id, text, done, note) VALUES (1, 'foo', 0, NULL), (2, 'bar', 1, 'baz')",
+            )
+            .unwrap();
+            stmt.exec().unwrap();
+        }
+
+        {
+            let mut stmt: Statement<NoParams, OwnedCols<Todo>> =
+                Statement::new(&conn, "SELECT ID, text, done, note FROM tbl ORDER BY id").unwrap();
+            assert_eq!(
+                vec![
+                    Todo {
+                        id: 1,
+                        text: "foo".to_owned(),
+                        done: false,
+                        note: None,
+                    },
+                    Todo {
+                        id: 2,
+                        text: "bar".to_owned(),
+                        done: true,
+                        note: Some("baz".to_owned()),
+                    },
+                ],
+                stmt.query_all(()).unwrap()
+            );
+        }
+
+        {
+            let mut stmt: Statement<Params<i32>, OwnedCols<(String, Vec<u8>)>> =
+                Statement::new(&conn, "SELECT text, text FROM tbl WHERE id = ?").unwrap();
+            assert_eq!(
+                ("bar".to_owned(), b"bar".to_vec()),
+                stmt.query_one(2).unwrap()
+            );
+        }
+
+        {
+            let mut stmt: Statement<NoParams, OwnedCols<i64>> =
+                Statement::new(&conn, "SELECT COUNT(*) FROM tbl").unwrap();
+            assert_eq!(2, stmt.query_one(()).unwrap());
+        }
+    }
+}
@@ -0,0 +1,203 @@
+/*
+This file is part of serde-odbc.
+
+serde-odbc is free software: you can redistribute it and/or modify
+it under the terms of the GNU Lesser General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+serde-odbc is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Lesser General Public License for more details.
+
+You should have received a copy of the GNU Lesser General Public License
+along with serde-odbc.  If not, see <http://www.gnu.org/licenses/>.
+*/
+use std::any::type_name;
+use std::marker::PhantomData;
+use std::mem::offset_of;
+
+use serde::de::{Deserialize, Deserializer, Error as _};
+use serde::ser::{Serialize, Serializer};
+
+use crate::bindable::{Bindable, FieldBinder, SqlField};
+use crate::error::{Error, Result};
+use crate::metadata::FieldDescription;
+
+pub trait Discriminant: Copy {
+    fn discriminant(self) -> i32;
+    fn from_discriminant(value: i32) -> Option<Self>;
+}
+
+#[macro_export]
+macro_rules! impl_discriminant {
+    ($type:ty { $($variant:ident),* $(,)? }) => {
+        impl $crate::Discriminant for $type {
+            fn discriminant(self) -> i32 {
+                self as i32
+            }
+
+            fn from_discriminant(value: i32) -> Option<Self> {
+                $(
+                    if value == <$type>::$variant as i32 {
+                        return Some(<$type>::$variant);
+                    }
+                )*
+
+                None
+            }
+        }
+    };
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Enum<E> {
+    value: i32,
+    _marker: PhantomData<E>,
+}
+
+impl<E> Serialize for Enum<E> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_newtype_struct("Enum", &self.value)
+    }
+}
+
+impl<'de, E: Discriminant> Deserialize<'de> for Enum<E> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let value = Self::from_raw(i32::deserialize(deserializer)?);
+
+        value.get().map_err(D::Error::custom)?;
+
+        Ok(value)
+    }
+}
+
+unsafe impl<E> Bindable for Enum<E> {
+    fn bind_fields(binder: &mut FieldBinder<'_>, offset: usize) -> Result<()> {
+        i32::bind_fields(binder, offset + offset_of!(Self, value))
+    }
+}
+
+impl<E> SqlField for Enum<E> {
+    fn describe(name: &'static str) -> FieldDescription {
+        i32::describe(name)
+    }
+}
+
+impl<E> Enum<E> {
+    pub fn from_raw(value: i32) -> Self {
+        Enum {
+            value,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn raw(&self) -> i32 {
+        self.value
+    }
+}
+
+impl<E: Discriminant> Enum<E> {
+    pub fn new(value: E) -> Self {
+        Self::from_raw(value.discriminant())
+    }
+
+    pub fn get(&self) -> Result<E> {
+        E::from_discriminant(self.value).ok_or_else(|| {
+            Error::Bind(format!(
+                "{} is not a discriminant of {}",
+                self.value,
+                type_name::<E>()
+            ))
+        })
+    }
+
+    pub fn set(&mut self, value: E) {
+        self.value = value.discriminant();
+    }
+}
+
+impl<E> Default for Enum<E> {
+    fn default() -> Self {
+        Self::from_raw(0)
+    }
+}
+
+impl<E: Discriminant> From<E> for Enum<E> {
+    fn from(value: E) -> Self {
+        Self::new(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{
+        col_binding::{Cols, NoCols},
+        connection::{Connection, Environment},
+        dynamic::OwnedCols,
+        param_binding::{NoParams, Params},
+        statement::Statement,
+        tests::CONN_STR,
+    };
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    #[repr(i32)]
+    enum Status {
+        Open = 1,
+        Closed = 2,
+    }
+
+    impl_discriminant!(Status { Open, Closed });
+
+    #[test]
+    fn bind_enum() {
+        let env = Environment::new().unwrap();
+        let conn = Connection::new(&env, CONN_STR).unwrap();
+
+        {
+            let mut stmt: Statement<NoParams, NoCols> = Statement::new(
+                &conn,
+                "CREATE TEMPORARY TABLE tbl (status INTEGER NOT NULL)",
+            )
+            .unwrap();
+            stmt.exec().unwrap();
+        }
+
+        {
+            let mut stmt: Statement<Params<Enum<Status>>, NoCols> =
+                Statement::new(&conn, "INSERT INTO tbl (status) VALUES (?)").unwrap();
+            stmt.params().set(Status::Closed);
+            stmt.exec().unwrap();
+        }
+
+        {
+            let mut stmt: Statement<NoParams, Cols<Enum<Status>>> =
+                Statement::new(&conn, "SELECT status FROM tbl").unwrap();
+            let status = stmt.query_one(()).unwrap();
+            assert_eq!(Status::Closed, status.get().unwrap());
+        }
+
+        {
+            let mut stmt: Statement<NoParams, Cols<Enum<Status>>> =
+                Statement::new(&conn, "SELECT 7").unwrap();
+            let status = stmt.query_one(()).unwrap();
+            assert_eq!(7, status.raw());
+            match status.get() {
+                Err(Error::Bind(_)) => (),
+                _ => panic!("unknown discriminant not rejected"),
+            }
+        }
+
+        {
+            let mut stmt: Statement<NoParams, OwnedCols<Enum<Status>>> =
+                Statement::new(&conn, "SELECT 7").unwrap();
+            match stmt.query_one(()) {
+                Err(Error::Serde(_)) => (),
+                _ => panic!("unknown discriminant not rejected"),
+            }
+        }
+    }
+}
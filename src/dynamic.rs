@@ -0,0 +1,501 @@
+/*
+This file is part of serde-odbc.
+
+serde-odbc is free software: you can redistribute it and/or modify
+it under the terms of the GNU Lesser General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+serde-odbc is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Lesser General Public License for more details.
+
+You should have received a copy of the GNU Lesser General Public License
+along with serde-odbc.  If not, see <http://www.gnu.org/licenses/>.
+*/
+use std::mem::size_of;
+use std::ops::Index;
+use std::ptr::null_mut;
+use std::slice::{from_mut, from_ref};
+use std::sync::Arc;
+
+use odbc_sys::{
+    SQLGetData, SqlCDataType, SqlDataType, SQLHANDLE, SQLHSTMT, SQLLEN, SQLPOINTER, SQLUSMALLINT,
+    SQL_C_BINARY, SQL_C_CHAR, SQL_C_DOUBLE, SQL_C_SBIGINT, SQL_C_TYPE_DATE, SQL_C_TYPE_TIME,
+    SQL_C_TYPE_TIMESTAMP, SQL_DATE_STRUCT, SQL_NO_DATA, SQL_NO_TOTAL, SQL_NULL_DATA,
+    SQL_TIMESTAMP_STRUCT, SQL_TIME_STRUCT,
+};
+
+use serde::de::DeserializeOwned;
+
+use super::col_binder::ColOptions;
+use super::col_binding::{not_bound, ColBinding};
+use super::datetime::{Date, Time, Timestamp};
+use super::error::{check_stmt, Error, Result};
+use super::metadata::{describe_cols, ColumnDescription};
+
+const MIN_BUFFER_LEN: usize = 64;
+const MAX_BUFFER_LEN: usize = 64 * 1024;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Null,
+    Integer(i64),
+    Float(f64),
+    Text(String),
+    Binary(Vec<u8>),
+    Date(Date),
+    Time(Time),
+    Timestamp(Timestamp),
+    Decimal(String),
+}
+
+impl Value {
+    pub fn is_null(&self) -> bool {
+        *self == Value::Null
+    }
+}
+
+macro_rules! impl_from {
+    ($type:ty, $variant:ident, $convert:expr) => {
+        impl From<$type> for Value {
+            fn from(value: $type) -> Self {
+                Value::$variant($convert(value))
+            }
+        }
+    };
+}
+
+impl_from!(i8, Integer, i64::from);
+impl_from!(i16, Integer, i64::from);
+impl_from!(i32, Integer, i64::from);
+impl_from!(i64, Integer, i64::from);
+impl_from!(u8, Integer, i64::from);
+impl_from!(u16, Integer, i64::from);
+impl_from!(u32, Integer, i64::from);
+impl_from!(bool, Integer, i64::from);
+impl_from!(f32, Float, f64::from);
+impl_from!(f64, Float, f64::from);
+impl_from!(&str, Text, str::to_owned);
+impl_from!(String, Text, String::from);
+impl_from!(Vec<u8>, Binary, Vec::from);
+impl_from!(&[u8], Binary, <[u8]>::to_vec);
+impl_from!(Date, Date, Date::from);
+impl_from!(Time, Time, Time::from);
+impl_from!(Timestamp, Timestamp, Timestamp::from);
+impl_from!(SQL_DATE_STRUCT, Date, Date::from);
+impl_from!(SQL_TIME_STRUCT, Time, Time::from);
+impl_from!(SQL_TIMESTAMP_STRUCT, Timestamp, Timestamp::from);
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Value::Null, Into::into)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Row {
+    names: Arc<[String]>,
+    values: Vec<Value>,
+}
+
+impl Row {
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    pub fn values(&self) -> &[Value] {
+        &self.values
+    }
+
+    pub fn into_values(self) -> Vec<Value> {
+        self.values
+    }
+
+    pub fn get(&self, col: usize) -> Option<&Value> {
+        self.values.get(col)
+    }
+
+    pub fn get_by_name(&self, name: &str) -> Option<&Value> {
+        let pos = self
+            .names
+            .iter()
+            .position(|col_name| col_name.eq_ignore_ascii_case(name))?;
+
+        self.values.get(pos)
+    }
+}
+
+impl Index<usize> for Row {
+    type Output = Value;
+
+    fn index(&self, col: usize) -> &Value {
+        &self.values[col]
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Kind {
+    Integer,
+    Float,
+    Text,
+    Binary,
+    Date,
+    Time,
+    Timestamp,
+    Decimal,
+}
+
+impl Kind {
+    fn of(col: &ColumnDescription) -> (Self, usize) {
+        let column_size = col.column_size as usize;
+
+        match col.data_type {
+            SqlDataType::SQL_EXT_TINYINT
+            | SqlDataType::SQL_SMALLINT
+            | SqlDataType::SQL_INTEGER
+            | SqlDataType::SQL_EXT_BIGINT
+            | SqlDataType::SQL_EXT_BIT => (Kind::Integer, 0),
+            SqlDataType::SQL_REAL | SqlDataType::SQL_FLOAT | SqlDataType::SQL_DOUBLE => {
+                (Kind::Float, 0)
+            }
+            SqlDataType::SQL_NUMERIC | SqlDataType::SQL_DECIMAL => {
+                // Leave room for sign, decimal point and terminator.
+                (Kind::Decimal, column_size + 3)
+            }
+            SqlDataType::SQL_DATETIME | SqlDataType::SQL_DATE => (Kind::Date, 0),
+            SqlDataType::SQL_EXT_TIME_OR_INTERVAL | SqlDataType::SQL_TIME => (Kind::Time, 0),
+            SqlDataType::SQL_EXT_TIMESTAMP | SqlDataType::SQL_TIMESTAMP => (Kind::Timestamp, 0),
+            SqlDataType::SQL_EXT_BINARY
+            | SqlDataType::SQL_EXT_VARBINARY
+            | SqlDataType::SQL_EXT_LONGVARBINARY => (Kind::Binary, column_size),
+            _ => (Kind::Text, column_size + 1),
+        }
+    }
+}
+
+pub struct DynCols {
+    stmt: SQLHSTMT,
+    kinds: Vec<Kind>,
+    row: Row,
+    buffer: Vec<u8>,
+}
+
+impl ColBinding for DynCols {
+    fn new() -> Self {
+        DynCols {
+            stmt: null_mut(),
+            kinds: Vec::new(),
+            row: Default::default(),
+            buffer: Vec::new(),
+        }
+    }
+
+    type Cols = Row;
+    fn cols(&self) -> &Self::Cols {
+        &self.row
+    }
+
+    type Row = Row;
+    fn rows(&self) -> &[Self::Row] {
+        from_ref(&self.row)
+    }
+    fn rows_mut(&mut self) -> &mut [Self::Row] {
+        from_mut(&mut self.row)
+    }
+
+    fn truncate(&mut self, _len: usize) {}
+
+    fn row_array_size(&self) -> usize {
+        1
+    }
+
+    unsafe fn bind(&mut self, stmt: SQLHSTMT, options: ColOptions) -> Result<()> {
+        // Columns are fetched as described by the result, so there are no fields to check.
+        if options.validate || options.by_name {
+            return Err(Error::Bind(
+                "dynamic columns cannot be validated or bound by name".to_owned(),
+            ));
+        }
+
+        let descriptions = describe_cols(stmt)?;
+
+        let mut buffer_len = MIN_BUFFER_LEN;
+
+        self.kinds = descriptions
+            .iter()
+            .map(|col| {
+                let (kind, len) = Kind::of(col);
+                buffer_len = buffer_len.max(len.min(MAX_BUFFER_LEN));
+                kind
+            })
+            .collect();
+
+        self.stmt = stmt;
+        self.buffer.resize(buffer_len, 0);
+        self.row = Row {
+            names: descriptions.into_iter().map(|col| col.name).collect(),
+            values: Vec::new(),
+        };
+
+        Ok(())
+    }
+
+    fn fetch(&mut self) -> Result<bool> {
+        let mut values = Vec::with_capacity(self.kinds.len());
+
+        for (col_nr, kind) in self.kinds.iter().enumerate() {
+            let col_nr = (col_nr + 1) as SQLUSMALLINT;
+
+            let value = unsafe {
+                match kind {
+                    Kind::Integer => {
+                        get_fixed(self.stmt, col_nr, SQL_C_SBIGINT)?.map(Value::Integer)
+                    }
+                    Kind::Float => get_fixed(self.stmt, col_nr, SQL_C_DOUBLE)?.map(Value::Float),
+                    Kind::Date => get_fixed(self.stmt, col_nr, SQL_C_TYPE_DATE)?.map(Value::Date),
+                    Kind::Time => get_fixed(self.stmt, col_nr, SQL_C_TYPE_TIME)?.map(Value::Time),
+                    Kind::Timestamp => {
+                        get_fixed(self.stmt, col_nr, SQL_C_TYPE_TIMESTAMP)?.map(Value::Timestamp)
+                    }
+                    Kind::Text => get_var(self.stmt, col_nr, SQL_C_CHAR, &mut self.buffer)?
+                        .map(|data| Value::Text(to_string(data))),
+                    Kind::Decimal => get_var(self.stmt, col_nr, SQL_C_CHAR, &mut self.buffer)?
+                        .map(|data| Value::Decimal(to_string(data))),
+                    Kind::Binary => get_var(self.stmt, col_nr, SQL_C_BINARY, &mut self.buffer)?
+                        .map(Value::Binary),
+                }
+            };
+
+            values.push(value.unwrap_or(Value::Null));
+        }
+
+        self.row.values = values;
+
+        Ok(true)
+    }
+
+    fn flush(&mut self) {}
+
+    fn stage(&mut self, _rows: &[Self::Row]) -> Result<usize> {
+        Err(not_bound())
+    }
+
+    fn invalidate(&mut self) {}
+}
+
+pub struct OwnedCols<T: DeserializeOwned> {
+    cols: DynCols,
+    data: Option<T>,
+}
+
+impl<T: DeserializeOwned> ColBinding for OwnedCols<T> {
+    fn new() -> Self {
+        OwnedCols {
+            cols: DynCols::new(),
+            data: None,
+        }
+    }
+
+    type Cols = Option<T>;
+    fn cols(&self) -> &Self::Cols {
+        &self.data
+    }
+
+    type Row = T;
+    fn rows(&self) -> &[Self::Row] {
+        self.data.as_slice()
+    }
+    fn rows_mut(&mut self) -> &mut [Self::Row] {
+        self.data.as_mut_slice()
+    }
+
+    fn truncate(&mut self, len: usize) {
+        if len == 0 {
+            self.data = None;
+        }
+    }
+
+    fn row_array_size(&self) -> usize {
+        1
+    }
+
+    unsafe fn bind(&mut self, stmt: SQLHSTMT, options: ColOptions) -> Result<()> {
+        self.data = None;
+
+        self.cols.bind(stmt, options)
+    }
+
+    fn fetch(&mut self) -> Result<bool> {
+        self.data = None;
+
+        self.cols.fetch()?;
+
+        self.data = Some(self.cols.row.decode()?);
+
+        Ok(true)
+    }
+
+    fn flush(&mut self) {}
+
+    fn stage(&mut self, _rows: &[Self::Row]) -> Result<usize> {
+        Err(not_bound())
+    }
+
+    fn invalidate(&mut self) {
+        self.cols.invalidate();
+    }
+}
+
+unsafe fn get_fixed<T: Copy + Default>(
+    stmt: SQLHSTMT,
+    col_nr: SQLUSMALLINT,
+    c_data_type: SqlCDataType,
+) -> Result<Option<T>> {
+    let mut value = T::default();
+    let mut indicator: SQLLEN = 0;
+
+    let rc = SQLGetData(
+        stmt,
+        col_nr,
+        c_data_type,
+        &mut value as *mut T as SQLPOINTER,
+        size_of::<T>() as SQLLEN,
+        &mut indicator,
+    );
+
+    check_stmt(rc, stmt as SQLHANDLE)?;
+
+    if indicator == SQL_NULL_DATA {
+        return Ok(None);
+    }
+
+    Ok(Some(value))
+}
+
+unsafe fn get_var(
+    stmt: SQLHSTMT,
+    col_nr: SQLUSMALLINT,
+    c_data_type: SqlCDataType,
+    buffer: &mut [u8],
+) -> Result<Option<Vec<u8>>> {
+    let terminator = (c_data_type == SQL_C_CHAR) as usize;
+
+    let mut data = Vec::new();
+
+    loop {
+        let mut indicator: SQLLEN = 0;
+
+        let rc = SQLGetData(
+            stmt,
+            col_nr,
+            c_data_type,
+            buffer.as_mut_ptr() as SQLPOINTER,
+            buffer.len() as SQLLEN,
+            &mut indicator,
+        );
+
+        if rc == SQL_NO_DATA {
+            break;
+        }
+
+        check_stmt(rc, stmt as SQLHANDLE)?;
+
+        if indicator == SQL_NULL_DATA {
+            return Ok(None);
+        }
+
+        // A truncated chunk fills the whole buffer and the driver keeps
+        // the remainder for the next call.
+        if indicator != SQL_NO_TOTAL && indicator as usize + terminator <= buffer.len() {
+            data.extend_from_slice(&buffer[..indicator as usize]);
+            break;
+        }
+
+        data.extend_from_slice(&buffer[..buffer.len() - terminator]);
+    }
+
+    Ok(Some(data))
+}
+
+fn to_string(data: Vec<u8>) -> String {
+    String::from_utf8(data)
+        .unwrap_or_else(|err| String::from_utf8_lossy(err.as_bytes()).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{
+        col_binding::NoCols,
+        connection::{Connection, Environment},
+        param_binding::NoParams,
+        statement::Statement,
+        tests::CONN_STR,
+    };
+
+    #[test]
+    fn fetch_dyn_rows() {
+        let env = Environment::new().unwrap();
+        let conn = Connection::new(&env, CONN_STR).unwrap();
+
+        {
+            let mut stmt: Statement<NoParams, NoCols> = Statement::new(
+                &conn,
+                "CREATE TEMPORARY TABLE tbl (id INTEGER NOT NULL, amount DOUBLE, text VARCHAR(4))",
+            )
+            .unwrap();
+            stmt.exec().unwrap();
+        }
+
+        {
+            let mut stmt: Statement<NoParams, NoCols> = Statement::new(
+                &conn,
+                "INSERT INTO tbl (id, amount, text) VALUES (1, 2.5, 'foobar'), (2, NULL, NULL)",
+            )
+            .unwrap();
+            stmt.exec().unwrap();
+        }
+
+        let mut stmt: Statement<NoParams, DynCols> =
+            Statement::new(&conn, "SELECT id, amount, text FROM tbl ORDER BY id").unwrap();
+
+        let rows = stmt.query_all(()).unwrap();
+        assert_eq!(2, rows.len());
+
+        assert_eq!(&["id", "amount", "text"][..], rows[0].names());
+        assert_eq!(Value::Integer(1), rows[0][0]);
+        assert_eq!(Value::Float(2.5), rows[0][1]);
+        assert_eq!(
+            Some(&Value::Text("foobar".to_owned())),
+            rows[0].get_by_name("TEXT")
+        );
+
+        assert_eq!(Value::Integer(2), rows[1][0]);
+        assert!(rows[1][1].is_null());
+        assert!(rows[1][2].is_null());
+    }
+
+    #[test]
+    fn reject_col_options() {
+        let env = Environment::new().unwrap();
+        let conn = Connection::new(&env, CONN_STR).unwrap();
+
+        let mut stmt: Statement<NoParams, DynCols> = Statement::new(&conn, "SELECT 1").unwrap();
+        stmt.set_validate_cols(true);
+        match stmt.exec() {
+            Err(Error::Bind(_)) => (),
+            _ => panic!("validation of dynamic columns not rejected"),
+        }
+    }
+}
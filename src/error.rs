@@ -18,13 +18,23 @@ use std::error;
 use std::fmt;
 use std::result;
 
-use odbc_sys::{SQLRETURN, SQL_NO_DATA, SQL_SUCCESS, SQL_SUCCESS_WITH_INFO};
-use serde::ser;
+use std::ptr::null_mut;
+
+use odbc_sys::{
+    SQLGetDiagRec, SQLHANDLE, SQLRETURN, SQL_HANDLE_STMT, SQL_NO_DATA, SQL_SUCCESS,
+    SQL_SUCCESS_WITH_INFO,
+};
+use serde::{de, ser};
 
 #[derive(Debug)]
 pub enum Error {
     Odbc(SQLRETURN),
     Serde(String),
+    Bind(String),
+    Unsupported(String, &'static str),
+    Cancelled,
+    NoRows,
+    TooManyRows,
 }
 
 pub type Result<T> = result::Result<T, Error>;
@@ -34,6 +44,11 @@ impl error::Error for Error {
         match *self {
             Error::Odbc(_) => "ODBC error",
             Error::Serde(_) => "Serde error",
+            Error::Bind(_) => "Binding error",
+            Error::Unsupported(_, _) => "Unsupported type",
+            Error::Cancelled => "Statement cancelled",
+            Error::NoRows => "No rows",
+            Error::TooManyRows => "Too many rows",
         }
     }
 }
@@ -43,6 +58,15 @@ impl fmt::Display for Error {
         match *self {
             Error::Odbc(rc) => write!(fmt, "ODBC error: {:?}", rc),
             Error::Serde(ref msg) => write!(fmt, "Serde error: {}", msg),
+            Error::Bind(ref msg) => write!(fmt, "Binding error: {}", msg),
+            Error::Unsupported(ref field, shape) => write!(
+                fmt,
+                "Unsupported type: field {} is serialized as {} which cannot be bound",
+                field, shape
+            ),
+            Error::Cancelled => write!(fmt, "Statement cancelled"),
+            Error::NoRows => write!(fmt, "Query returned no rows"),
+            Error::TooManyRows => write!(fmt, "Query returned more than one row"),
         }
     }
 }
@@ -53,6 +77,12 @@ impl ser::Error for Error {
     }
 }
 
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error::Serde(msg.to_string())
+    }
+}
+
 pub trait OdbcResult {
     fn check(self) -> Result<()>;
 }
@@ -65,3 +95,33 @@ impl OdbcResult for SQLRETURN {
         }
     }
 }
+
+pub unsafe fn check_stmt(rc: SQLRETURN, stmt: SQLHANDLE) -> Result<()> {
+    rc.check().map_err(|err| match stmt_state(stmt) {
+        Some(state) if &state == b"HY008" => Error::Cancelled,
+        _ => err,
+    })
+}
+
+// The SQLSTATE of the first diagnostic record of the last call using `stmt`.
+pub unsafe fn stmt_state(stmt: SQLHANDLE) -> Option<[u8; 5]> {
+    let mut state = [0; 6];
+
+    let diag_rc = SQLGetDiagRec(
+        SQL_HANDLE_STMT,
+        stmt,
+        1,
+        state.as_mut_ptr(),
+        null_mut(),
+        null_mut(),
+        0,
+        null_mut(),
+    );
+
+    match diag_rc {
+        SQL_SUCCESS | SQL_SUCCESS_WITH_INFO => {
+            Some([state[0], state[1], state[2], state[3], state[4]])
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,94 @@
+/*
+This file is part of serde-odbc.
+
+serde-odbc is free software: you can redistribute it and/or modify
+it under the terms of the GNU Lesser General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+serde-odbc is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Lesser General Public License for more details.
+
+You should have received a copy of the GNU Lesser General Public License
+along with serde-odbc.  If not, see <http://www.gnu.org/licenses/>.
+*/
+// Declarations missing from odbc-sys or declared there with enum-typed
+// out parameters which drivers are free to fill with other values.
+#![allow(non_snake_case)]
+
+use odbc_sys::{
+    SQLCHAR, SQLHSTMT, SQLINTEGER, SQLPOINTER, SQLRETURN, SQLSMALLINT, SQLULEN, SQLUSMALLINT,
+};
+
+pub const SQL_ATTR_QUERY_TIMEOUT: SQLINTEGER = 0;
+pub const SQL_ATTR_MAX_ROWS: SQLINTEGER = 1;
+pub const SQL_ATTR_CURSOR_TYPE: SQLINTEGER = 6;
+pub const SQL_ATTR_CONCURRENCY: SQLINTEGER = 7;
+pub const SQL_ATTR_ROW_NUMBER: SQLINTEGER = 14;
+
+pub const SQL_CURSOR_FORWARD_ONLY: SQLULEN = 0;
+pub const SQL_CURSOR_KEYSET_DRIVEN: SQLULEN = 1;
+pub const SQL_CURSOR_DYNAMIC: SQLULEN = 2;
+pub const SQL_CURSOR_STATIC: SQLULEN = 3;
+
+pub const SQL_CONCUR_READ_ONLY: SQLULEN = 1;
+pub const SQL_CONCUR_LOCK: SQLULEN = 2;
+pub const SQL_CONCUR_ROWVER: SQLULEN = 3;
+pub const SQL_CONCUR_VALUES: SQLULEN = 4;
+
+pub const SQL_UPDATE: SQLUSMALLINT = 2;
+pub const SQL_DELETE: SQLUSMALLINT = 3;
+
+pub const SQL_LOCK_NO_CHANGE: SQLUSMALLINT = 0;
+
+#[cfg_attr(windows, link(name = "odbc32"))]
+#[cfg_attr(not(windows), link(name = "odbc"))]
+extern "system" {
+    pub fn SQLDescribeCol(
+        hstmt: SQLHSTMT,
+        col_number: SQLUSMALLINT,
+        col_name: *mut SQLCHAR,
+        buffer_length: SQLSMALLINT,
+        name_length: *mut SQLSMALLINT,
+        data_type: *mut SQLSMALLINT,
+        col_size: *mut SQLULEN,
+        decimal_digits: *mut SQLSMALLINT,
+        nullable: *mut SQLSMALLINT,
+    ) -> SQLRETURN;
+
+    // odbc-sys only declares this for the attributes of `SqlStatementAttribute`.
+    pub fn SQLSetStmtAttr(
+        hstmt: SQLHSTMT,
+        attr: SQLINTEGER,
+        value: SQLPOINTER,
+        str_length: SQLINTEGER,
+    ) -> SQLRETURN;
+
+    pub fn SQLGetStmtAttr(
+        hstmt: SQLHSTMT,
+        attr: SQLINTEGER,
+        value: SQLPOINTER,
+        buffer_length: SQLINTEGER,
+        str_length: *mut SQLINTEGER,
+    ) -> SQLRETURN;
+
+    pub fn SQLSetPos(
+        hstmt: SQLHSTMT,
+        row_number: SQLULEN,
+        operation: SQLUSMALLINT,
+        lock_type: SQLUSMALLINT,
+    ) -> SQLRETURN;
+
+    pub fn SQLNumParams(hstmt: SQLHSTMT, param_count: *mut SQLSMALLINT) -> SQLRETURN;
+
+    pub fn SQLDescribeParam(
+        hstmt: SQLHSTMT,
+        param_number: SQLUSMALLINT,
+        data_type: *mut SQLSMALLINT,
+        param_size: *mut SQLULEN,
+        decimal_digits: *mut SQLSMALLINT,
+        nullable: *mut SQLSMALLINT,
+    ) -> SQLRETURN;
+}
@@ -0,0 +1,136 @@
+/*
+This file is part of serde-odbc.
+
+serde-odbc is free software: you can redistribute it and/or modify
+it under the terms of the GNU Lesser General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+serde-odbc is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Lesser General Public License for more details.
+
+You should have received a copy of the GNU Lesser General Public License
+along with serde-odbc.  If not, see <http://www.gnu.org/licenses/>.
+*/
+use std::mem::offset_of;
+use std::ops::{Deref, DerefMut};
+
+use odbc_sys::{SQL_PARAM_INPUT_OUTPUT, SQL_PARAM_OUTPUT};
+use serde::ser::{Serialize, Serializer};
+
+use crate::bindable::{Bindable, FieldBinder, SqlField};
+use crate::binder::with_input_output;
+use crate::error;
+use crate::metadata::FieldDescription;
+
+// Also used for the return value of `{? = CALL proc(...)}`
+// which ODBC binds as an output parameter.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Out<T> {
+    value: T,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct InOut<T> {
+    value: T,
+}
+
+macro_rules! impl_input_output {
+    ($type:ident, $input_output:ident) => {
+        impl<T: Serialize> Serialize for $type<T> {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                with_input_output($input_output, || {
+                    serializer.serialize_newtype_struct(stringify!($type), &self.value)
+                })
+            }
+        }
+
+        unsafe impl<T: Bindable> Bindable for $type<T> {
+            fn bind_fields(binder: &mut FieldBinder<'_>, offset: usize) -> error::Result<()> {
+                with_input_output($input_output, || {
+                    T::bind_fields(binder, offset + offset_of!(Self, value))
+                })
+            }
+        }
+
+        impl<T: SqlField> SqlField for $type<T> {
+            fn describe(name: &'static str) -> FieldDescription {
+                T::describe(name)
+            }
+        }
+
+        impl<T> $type<T> {
+            pub fn new(value: T) -> Self {
+                Self { value }
+            }
+
+            pub fn into_inner(self) -> T {
+                self.value
+            }
+        }
+
+        impl<T> Deref for $type<T> {
+            type Target = T;
+
+            fn deref(&self) -> &T {
+                &self.value
+            }
+        }
+
+        impl<T> DerefMut for $type<T> {
+            fn deref_mut(&mut self) -> &mut T {
+                &mut self.value
+            }
+        }
+    };
+}
+
+impl_input_output!(Out, SQL_PARAM_OUTPUT);
+impl_input_output!(InOut, SQL_PARAM_INPUT_OUTPUT);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use generic_array::typenum::U8;
+
+    use crate::{
+        col_binding::Cols,
+        connection::{Connection, Environment},
+        param_binding::Params,
+        statement::Statement,
+        string::String,
+        tests::CONN_STR,
+    };
+
+    #[test]
+    fn bind_input_output_param() {
+        let env = Environment::new().unwrap();
+        let conn = Connection::new(&env, CONN_STR).unwrap();
+
+        let mut stmt: Statement<Params<InOut<i32>>, Cols<i32>> =
+            Statement::new(&conn, "SELECT ?").unwrap();
+        **stmt.params() = 42;
+        stmt.exec().unwrap();
+        assert!(stmt.fetch().unwrap());
+        assert_eq!(42, *stmt.cols());
+        assert!(!stmt.fetch().unwrap());
+    }
+
+    #[test]
+    fn bind_input_output_str() {
+        let env = Environment::new().unwrap();
+        let conn = Connection::new(&env, CONN_STR).unwrap();
+
+        let mut stmt: Statement<Params<InOut<String<U8>>>, Cols<String<U8>>> =
+            Statement::new(&conn, "SELECT ?").unwrap();
+        stmt.params().extend_from_slice(b"foobar");
+        stmt.exec().unwrap();
+        assert!(stmt.fetch().unwrap());
+        assert_eq!(&b"foobar"[..], stmt.cols().as_slice());
+        assert_eq!(&b"foobar"[..], stmt.params().as_slice());
+        assert!(!stmt.fetch().unwrap());
+    }
+}
@@ -14,27 +14,56 @@ GNU Lesser General Public License for more details.
 You should have received a copy of the GNU Lesser General Public License
 along with serde-odbc.  If not, see <http://www.gnu.org/licenses/>.
 */
+// Lets `#[derive(Bindable)]` refer to `::serde_odbc` within this crate.
+extern crate self as serde_odbc;
+
 mod bind_types;
+mod bindable;
 mod binder;
+mod cache;
+mod cancel;
 mod col_binder;
 mod col_binding;
 mod connection;
+mod cursor;
+mod datetime;
+mod de;
+mod discriminant;
+mod dynamic;
 mod error;
+mod ffi;
+mod input_output;
+mod metadata;
 mod nullable;
 mod param_binder;
 mod param_binding;
+mod placeholders;
 mod statement;
 mod string;
 
+pub use bindable::{
+    BindWith, Bindable, FieldBinder, OdbcParams, OdbcRow, Offsets, Serde, SqlField,
+};
+pub use cache::CachedStatement;
+pub use cancel::CancelHandle;
 pub use col_binding::{Cols, NoCols, RowSet};
 pub use connection::*;
+pub use cursor::*;
+pub use datetime::{Date, Time, Timestamp};
+pub use discriminant::{Discriminant, Enum};
+pub use dynamic::{DynCols, OwnedCols, Row, Value};
 pub use error::{Error, Result};
+pub use input_output::*;
+pub use metadata::{ColumnDescription, FieldDescription, ParamDescription};
 pub use nullable::*;
-pub use param_binding::{NoParams, ParamSet, Params};
+pub use param_binding::{DynParams, NoParams, ParamSet, Params};
+pub use placeholders::expand_placeholder;
 pub use statement::*;
 pub use string::*;
 
 pub use generic_array;
+pub use odbc_sys;
+pub use serde_odbc_derive::{Bindable, OdbcParams, OdbcRow};
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,256 @@
+/*
+This file is part of serde-odbc.
+
+serde-odbc is free software: you can redistribute it and/or modify
+it under the terms of the GNU Lesser General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+serde-odbc is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Lesser General Public License for more details.
+
+You should have received a copy of the GNU Lesser General Public License
+along with serde-odbc.  If not, see <http://www.gnu.org/licenses/>.
+*/
+use odbc_sys::{
+    SQLNumResultCols, SqlCDataType, SqlDataType, SQLHSTMT, SQLSMALLINT, SQLULEN, SQLUSMALLINT,
+};
+
+use crate::error::{OdbcResult, Result};
+use crate::ffi::{SQLDescribeCol, SQLDescribeParam, SQLNumParams};
+
+#[derive(Clone, Debug)]
+pub struct ColumnDescription {
+    pub name: String,
+    pub data_type: SqlDataType,
+    pub column_size: SQLULEN,
+    pub decimal_digits: SQLSMALLINT,
+    pub nullable: Option<bool>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ParamDescription {
+    pub data_type: SqlDataType,
+    pub param_size: SQLULEN,
+    pub decimal_digits: SQLSMALLINT,
+    pub nullable: Option<bool>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FieldDescription {
+    pub name: &'static str,
+    pub c_data_type: SqlCDataType,
+    pub data_type: SqlDataType,
+    pub column_size: SQLULEN,
+    pub nullable: bool,
+}
+
+pub unsafe fn describe_cols(stmt: SQLHSTMT) -> Result<Vec<ColumnDescription>> {
+    let mut count: SQLSMALLINT = 0;
+
+    SQLNumResultCols(stmt, &mut count).check()?;
+
+    (1..=count as SQLUSMALLINT)
+        .map(|col_nr| describe_col(stmt, col_nr))
+        .collect()
+}
+
+unsafe fn describe_col(stmt: SQLHSTMT, col_nr: SQLUSMALLINT) -> Result<ColumnDescription> {
+    let mut name = vec![0; 64];
+    let mut name_length: SQLSMALLINT = 0;
+    let mut data_type: SQLSMALLINT = 0;
+    let mut column_size: SQLULEN = 0;
+    let mut decimal_digits: SQLSMALLINT = 0;
+    let mut nullable: SQLSMALLINT = 0;
+
+    loop {
+        SQLDescribeCol(
+            stmt,
+            col_nr,
+            name.as_mut_ptr(),
+            name.len() as SQLSMALLINT,
+            &mut name_length,
+            &mut data_type,
+            &mut column_size,
+            &mut decimal_digits,
+            &mut nullable,
+        )
+        .check()?;
+
+        if (name_length as usize) < name.len() {
+            break;
+        }
+
+        name.resize(name_length as usize + 1, 0);
+    }
+
+    name.truncate(name_length as usize);
+
+    Ok(ColumnDescription {
+        name: String::from_utf8_lossy(&name).into_owned(),
+        data_type: to_data_type(data_type),
+        column_size,
+        decimal_digits,
+        nullable: to_nullable(nullable),
+    })
+}
+
+pub unsafe fn describe_params(stmt: SQLHSTMT) -> Result<Vec<ParamDescription>> {
+    let mut count: SQLSMALLINT = 0;
+
+    SQLNumParams(stmt, &mut count).check()?;
+
+    (1..=count as SQLUSMALLINT)
+        .map(|param_nr| describe_param(stmt, param_nr))
+        .collect()
+}
+
+unsafe fn describe_param(stmt: SQLHSTMT, param_nr: SQLUSMALLINT) -> Result<ParamDescription> {
+    let mut data_type: SQLSMALLINT = 0;
+    let mut param_size: SQLULEN = 0;
+    let mut decimal_digits: SQLSMALLINT = 0;
+    let mut nullable: SQLSMALLINT = 0;
+
+    SQLDescribeParam(
+        stmt,
+        param_nr,
+        &mut data_type,
+        &mut param_size,
+        &mut decimal_digits,
+        &mut nullable,
+    )
+    .check()?;
+
+    Ok(ParamDescription {
+        data_type: to_data_type(data_type),
+        param_size,
+        decimal_digits,
+        nullable: to_nullable(nullable),
+    })
+}
+
+fn to_nullable(nullable: SQLSMALLINT) -> Option<bool> {
+    match nullable {
+        0 => Some(false),
+        1 => Some(true),
+        _ => None,
+    }
+}
+
+pub fn to_data_type(data_type: SQLSMALLINT) -> SqlDataType {
+    use SqlDataType::*;
+
+    match data_type {
+        1 => SQL_CHAR,
+        2 => SQL_NUMERIC,
+        3 => SQL_DECIMAL,
+        4 => SQL_INTEGER,
+        5 => SQL_SMALLINT,
+        6 => SQL_FLOAT,
+        7 => SQL_REAL,
+        8 => SQL_DOUBLE,
+        9 => SQL_DATETIME,
+        10 => SQL_EXT_TIME_OR_INTERVAL,
+        11 => SQL_EXT_TIMESTAMP,
+        12 => SQL_VARCHAR,
+        91 => SQL_DATE,
+        92 => SQL_TIME,
+        93 => SQL_TIMESTAMP,
+        -1 => SQL_EXT_LONGVARCHAR,
+        -2 => SQL_EXT_BINARY,
+        -3 => SQL_EXT_VARBINARY,
+        -4 => SQL_EXT_LONGVARBINARY,
+        -5 => SQL_EXT_BIGINT,
+        -6 => SQL_EXT_TINYINT,
+        -7 => SQL_EXT_BIT,
+        -8 => SQL_EXT_WCHAR,
+        -9 => SQL_EXT_WVARCHAR,
+        -10 => SQL_EXT_WLONGVARCHAR,
+        -11 => SQL_EXT_GUID,
+        -150 => SQL_SS_VARIANT,
+        -151 => SQL_SS_UDT,
+        -152 => SQL_SS_XML,
+        -153 => SQL_SS_TABLE,
+        -154 => SQL_SS_TIME2,
+        -155 => SQL_SS_TIMESTAMPOFFSET,
+        _ => SQL_UNKNOWN_TYPE,
+    }
+}
+
+pub fn is_compatible(c_data_type: SqlCDataType, data_type: SqlDataType) -> bool {
+    use SqlCDataType::*;
+    use SqlDataType::*;
+
+    let is_integer = matches!(
+        data_type,
+        SQL_SMALLINT | SQL_INTEGER | SQL_EXT_TINYINT | SQL_EXT_BIGINT | SQL_EXT_BIT
+    );
+    let is_numeric = matches!(data_type, SQL_NUMERIC | SQL_DECIMAL);
+    let is_float = matches!(data_type, SQL_REAL | SQL_FLOAT | SQL_DOUBLE);
+    let is_date = matches!(data_type, SQL_DATETIME | SQL_DATE);
+    let is_time = matches!(data_type, SQL_EXT_TIME_OR_INTERVAL | SQL_TIME);
+    let is_timestamp = matches!(data_type, SQL_EXT_TIMESTAMP | SQL_TIMESTAMP);
+
+    match c_data_type {
+        _ if data_type == SQL_UNKNOWN_TYPE => true,
+        SQL_C_STINYINT | SQL_C_SSHORT | SQL_C_SLONG | SQL_C_SBIGINT | SQL_C_UTINYINT
+        | SQL_C_USHORT | SQL_C_ULONG | SQL_C_UBIGINT | SQL_C_BIT => is_integer || is_numeric,
+        SQL_C_FLOAT | SQL_C_DOUBLE => is_integer || is_numeric || is_float,
+        SQL_C_TYPE_DATE => is_date || is_timestamp,
+        SQL_C_TYPE_TIME => is_time || is_timestamp,
+        SQL_C_TYPE_TIMESTAMP => is_date || is_time || is_timestamp,
+        SQL_C_CHAR => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{
+        col_binding::NoCols,
+        connection::{Connection, Environment},
+        param_binding::{NoParams, Params},
+        statement::Statement,
+        tests::CONN_STR,
+    };
+
+    #[test]
+    fn describe_result_cols() {
+        let env = Environment::new().unwrap();
+        let conn = Connection::new(&env, CONN_STR).unwrap();
+
+        {
+            let mut stmt: Statement<NoParams, NoCols> = Statement::new(
+                &conn,
+                "CREATE TEMPORARY TABLE tbl (id INTEGER NOT NULL, text VARCHAR(32))",
+            )
+            .unwrap();
+            stmt.exec().unwrap();
+        }
+
+        let stmt: Statement<NoParams, NoCols> =
+            Statement::new(&conn, "SELECT id, text AS label FROM tbl").unwrap();
+        let cols = stmt.columns().unwrap();
+        assert_eq!(2, cols.len());
+
+        assert_eq!("id", cols[0].name);
+        assert_eq!(SqlDataType::SQL_INTEGER, cols[0].data_type);
+
+        assert_eq!("label", cols[1].name);
+        assert_eq!(SqlDataType::SQL_VARCHAR, cols[1].data_type);
+    }
+
+    #[test]
+    fn describe_stmt_params() {
+        let env = Environment::new().unwrap();
+        let conn = Connection::new(&env, CONN_STR).unwrap();
+
+        let stmt: Statement<Params<(i32, i32)>, NoCols> =
+            Statement::new(&conn, "SELECT ?, ?").unwrap();
+        assert_eq!(2, stmt.parameters().unwrap().len());
+    }
+}
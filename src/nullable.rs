@@ -14,12 +14,15 @@ GNU Lesser General Public License for more details.
 You should have received a copy of the GNU Lesser General Public License
 along with serde-odbc.  If not, see <http://www.gnu.org/licenses/>.
 */
-use std::mem::size_of;
+use std::mem::{offset_of, size_of};
 
 use odbc_sys::{SQLLEN, SQL_NULL_DATA};
-use serde::ser::{Serialize, SerializeStruct, Serializer};
+use serde::ser::{Serialize, Serializer};
 
+use crate::bindable::{Bindable, FieldBinder, SqlField};
 use crate::binder::with_indicator;
+use crate::error;
+use crate::metadata::FieldDescription;
 
 #[derive(Clone, Copy, Debug)]
 pub struct Nullable<T> {
@@ -29,11 +32,26 @@ pub struct Nullable<T> {
 
 impl<T: Serialize> Serialize for Nullable<T> {
     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
-        let mut serializer = serializer.serialize_struct("Nullable", 1)?;
         with_indicator(&self.indicator as *const _ as *mut _, || {
-            serializer.serialize_field("value", &self.value)
-        })?;
-        serializer.end()
+            serializer.serialize_newtype_struct("Nullable", &self.value)
+        })
+    }
+}
+
+unsafe impl<T: Bindable> Bindable for Nullable<T> {
+    fn bind_fields(binder: &mut FieldBinder<'_>, offset: usize) -> error::Result<()> {
+        binder.with_indicator(offset + offset_of!(Self, indicator), |binder| {
+            T::bind_fields(binder, offset + offset_of!(Self, value))
+        })
+    }
+}
+
+impl<T: SqlField> SqlField for Nullable<T> {
+    fn describe(name: &'static str) -> FieldDescription {
+        FieldDescription {
+            nullable: true,
+            ..T::describe(name)
+        }
     }
 }
 
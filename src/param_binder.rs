@@ -15,73 +15,478 @@ You should have received a copy of the GNU Lesser General Public License
 along with serde-odbc.  If not, see <http://www.gnu.org/licenses/>.
 */
 use std::mem::size_of;
+use std::ptr::null_mut;
 
 use odbc_sys::{
-    SQLBindParameter, SQLHSTMT, SQLLEN, SQLPOINTER, SQLULEN, SQLUSMALLINT, SQL_C_CHAR,
-    SQL_PARAM_INPUT, SQL_VARCHAR,
+    SQLBindParameter, SqlDataType, SQLHSTMT, SQLLEN, SQLPOINTER, SQLSMALLINT, SQLULEN,
+    SQLUSMALLINT, SQL_C_BINARY, SQL_C_CHAR, SQL_C_DOUBLE, SQL_C_SBIGINT, SQL_C_TYPE_DATE,
+    SQL_C_TYPE_TIME, SQL_C_TYPE_TIMESTAMP, SQL_DATE, SQL_DECIMAL, SQL_DOUBLE, SQL_EXT_BIGINT,
+    SQL_EXT_VARBINARY, SQL_NULL_DATA, SQL_PARAM_INPUT, SQL_TIME, SQL_TIMESTAMP, SQL_VARCHAR,
 };
 use serde::ser::Serialize;
 
-use super::bind_types::BindTypes;
-use super::binder::{Binder, BinderImpl};
-use super::error::{OdbcResult, Result};
+use super::bind_types::BindType;
+use super::bindable::{Bindable, FieldBinder};
+use super::binder::{input_output, Binder, BinderImpl, FieldPath, SharedIndicators};
+use super::dynamic::Value;
+use super::error::{Error, OdbcResult, Result};
+use super::metadata::{describe_params, ParamDescription};
 
-struct ParamBinder {
+#[derive(Default)]
+pub struct ParamOptions {
+    pub names: Vec<String>,
+    pub describe: bool,
+}
+
+struct ParamBinder<'a> {
     stmt: SQLHSTMT,
+    options: &'a ParamOptions,
+    descriptions: Vec<ParamDescription>,
     param_nr: SQLUSMALLINT,
+    bound_names: Vec<bool>,
+    bound_params: usize,
+    value_dependent: bool,
+}
+
+static NULL_INDICATOR: SQLLEN = SQL_NULL_DATA;
+
+pub unsafe fn bind_params<P: Serialize>(
+    stmt: SQLHSTMT,
+    params: &P,
+    options: &ParamOptions,
+) -> Result<(bool, SharedIndicators)> {
+    bind_params_with(stmt, options, |binder| Binder::bind(binder, params))
+}
+
+pub unsafe fn bind_param_fields<P: Bindable>(
+    stmt: SQLHSTMT,
+    params: &P,
+    options: &ParamOptions,
+) -> Result<(bool, SharedIndicators)> {
+    bind_params_with(stmt, options, |binder| FieldBinder::bind(binder, params))
 }
 
-pub unsafe fn bind_params<P: Serialize>(stmt: SQLHSTMT, params: &P) -> Result<()> {
-    Binder::bind(ParamBinder { stmt, param_nr: 0 }, params)
+unsafe fn bind_params_with<'a, F>(
+    stmt: SQLHSTMT,
+    options: &'a ParamOptions,
+    f: F,
+) -> Result<(bool, SharedIndicators)>
+where
+    F: FnOnce(ParamBinder<'a>) -> Result<(ParamBinder<'a>, SharedIndicators)>,
+{
+    let descriptions = if options.describe {
+        describe_params(stmt)?
+    } else {
+        Vec::new()
+    };
+
+    let (binder, shared) = f(ParamBinder {
+        stmt,
+        options,
+        descriptions,
+        param_nr: 0,
+        bound_names: vec![false; options.names.len()],
+        bound_params: 0,
+        value_dependent: false,
+    })?;
+
+    if let Some(pos) = binder.bound_names.iter().position(|bound| !bound) {
+        return Err(Error::Bind(format!(
+            "parameter :{} is not bound by any field",
+            options.names[pos]
+        )));
+    }
+
+    if options.describe && binder.descriptions.len() != binder.bound_params {
+        return Err(Error::Bind(format!(
+            "{} parameters are bound but the statement has {}",
+            binder.bound_params,
+            binder.descriptions.len()
+        )));
+    }
+
+    Ok((binder.value_dependent, shared))
+}
+
+pub unsafe fn bind_values(
+    stmt: SQLHSTMT,
+    values: &[Value],
+    indicators: &mut Vec<SQLLEN>,
+    options: &ParamOptions,
+) -> Result<()> {
+    if !options.names.is_empty() {
+        return Err(Error::Bind(
+            "dynamic parameters cannot be bound to named placeholders".to_owned(),
+        ));
+    }
+
+    let descriptions = if options.describe {
+        describe_params(stmt)?
+    } else {
+        Vec::new()
+    };
+
+    let mut binder = ParamBinder {
+        stmt,
+        options,
+        descriptions,
+        param_nr: 0,
+        bound_names: Vec::new(),
+        bound_params: 0,
+        value_dependent: false,
+    };
+
+    indicators.clear();
+    indicators.resize(values.len(), 0);
+
+    for (value, indicator) in values.iter().zip(indicators.iter_mut()) {
+        binder.bind_value(value, indicator)?;
+    }
+
+    if options.describe && binder.descriptions.len() != binder.bound_params {
+        return Err(Error::Bind(format!(
+            "{} parameters are bound but the statement has {}",
+            binder.bound_params,
+            binder.descriptions.len()
+        )));
+    }
+
+    Ok(())
 }
 
-impl BinderImpl for ParamBinder {
-    fn bind<T: BindTypes>(
+impl ParamBinder<'_> {
+    unsafe fn bind_value(&mut self, value: &Value, indicator: &mut SQLLEN) -> Result<()> {
+        fn fixed<T>(value: &T) -> (SQLPOINTER, usize) {
+            (value as *const T as SQLPOINTER, size_of::<T>())
+        }
+
+        fn var(value: &[u8]) -> (SQLPOINTER, usize) {
+            (value.as_ptr() as SQLPOINTER, value.len())
+        }
+
+        let (c_data_type, data_type, param_size, decimal_digits, (value_ptr, length)) = match value
+        {
+            Value::Null => (SQL_C_CHAR, SQL_VARCHAR, 1, 0, var(&[])),
+            Value::Integer(value) => (SQL_C_SBIGINT, SQL_EXT_BIGINT, 0, 0, fixed(value)),
+            Value::Float(value) => (SQL_C_DOUBLE, SQL_DOUBLE, 0, 0, fixed(value)),
+            Value::Text(value) => (
+                SQL_C_CHAR,
+                SQL_VARCHAR,
+                value.len(),
+                0,
+                var(value.as_bytes()),
+            ),
+            Value::Decimal(value) => {
+                let scale = value.find('.').map_or(0, |pos| value.len() - pos - 1);
+
+                (
+                    SQL_C_CHAR,
+                    SQL_DECIMAL,
+                    value.len(),
+                    scale,
+                    var(value.as_bytes()),
+                )
+            }
+            Value::Binary(value) => (SQL_C_BINARY, SQL_EXT_VARBINARY, value.len(), 0, var(value)),
+            Value::Date(value) => (SQL_C_TYPE_DATE, SQL_DATE, 10, 0, fixed(value)),
+            Value::Time(value) => (SQL_C_TYPE_TIME, SQL_TIME, 8, 0, fixed(value)),
+            Value::Timestamp(value) => (SQL_C_TYPE_TIMESTAMP, SQL_TIMESTAMP, 29, 9, fixed(value)),
+        };
+
+        *indicator = match value {
+            Value::Null => SQL_NULL_DATA,
+            _ => length as SQLLEN,
+        };
+
+        self.param_nr += 1;
+
+        let (data_type, param_size, decimal_digits) = self.param_type(
+            &FieldPath::default(),
+            self.param_nr,
+            data_type,
+            param_size as SQLULEN,
+            decimal_digits as SQLSMALLINT,
+        )?;
+
+        SQLBindParameter(
+            self.stmt,
+            self.param_nr,
+            SQL_PARAM_INPUT,
+            c_data_type,
+            data_type,
+            param_size,
+            decimal_digits,
+            value_ptr,
+            length as SQLLEN,
+            indicator,
+        )
+        .check()
+    }
+
+    fn param_nrs(&mut self, field: &FieldPath) -> Result<Vec<SQLUSMALLINT>> {
+        if self.options.names.is_empty() {
+            self.param_nr += 1;
+
+            return Ok(vec![self.param_nr]);
+        }
+
+        let name = field.name().unwrap_or_default();
+
+        let mut param_nrs = Vec::new();
+
+        for (pos, param_name) in self.options.names.iter().enumerate() {
+            if param_name.eq_ignore_ascii_case(name) {
+                self.bound_names[pos] = true;
+                param_nrs.push((pos + 1) as SQLUSMALLINT);
+            }
+        }
+
+        if param_nrs.is_empty() {
+            return Err(Error::Bind(format!(
+                "field {} does not match any named parameter",
+                field
+            )));
+        }
+
+        Ok(param_nrs)
+    }
+
+    fn param_type(
+        &mut self,
+        field: &FieldPath,
+        param_nr: SQLUSMALLINT,
+        data_type: SqlDataType,
+        param_size: SQLULEN,
+        decimal_digits: SQLSMALLINT,
+    ) -> Result<(SqlDataType, SQLULEN, SQLSMALLINT)> {
+        self.bound_params += 1;
+
+        if !self.options.describe {
+            return Ok((data_type, param_size, decimal_digits));
+        }
+
+        let param = self
+            .descriptions
+            .get(param_nr as usize - 1)
+            .ok_or_else(|| {
+                Error::Bind(format!(
+                    "field {} is bound to parameter {} but the statement has only {}",
+                    field,
+                    param_nr,
+                    self.descriptions.len()
+                ))
+            })?;
+
+        Ok((param.data_type, param.param_size, param.decimal_digits))
+    }
+}
+
+impl BinderImpl for ParamBinder<'_> {
+    fn bind(
         &mut self,
+        field: &FieldPath,
+        bind_type: BindType,
         value_ptr: SQLPOINTER,
         indicator_ptr: *mut SQLLEN,
     ) -> Result<()> {
-        self.param_nr += 1;
+        for param_nr in self.param_nrs(field)? {
+            let (data_type, param_size, decimal_digits) = self.param_type(
+                field,
+                param_nr,
+                bind_type.data_type,
+                bind_type.column_size,
+                bind_type.decimal_digits,
+            )?;
 
-        unsafe {
-            SQLBindParameter(
-                self.stmt,
-                self.param_nr,
-                SQL_PARAM_INPUT,
-                T::c_data_type(),
-                T::data_type(),
-                0,
-                0,
-                value_ptr,
-                size_of::<T>() as SQLLEN,
-                indicator_ptr,
-            )
+            unsafe {
+                SQLBindParameter(
+                    self.stmt,
+                    param_nr,
+                    input_output(),
+                    bind_type.c_data_type,
+                    data_type,
+                    param_size,
+                    decimal_digits,
+                    value_ptr,
+                    bind_type.size as SQLLEN,
+                    indicator_ptr,
+                )
+            }
+            .check()?;
         }
-        .check()
+
+        Ok(())
     }
 
     fn bind_str(
         &mut self,
+        field: &FieldPath,
         length: usize,
         value_ptr: SQLPOINTER,
         indicator_ptr: *mut SQLLEN,
+        _nullable: bool,
     ) -> Result<()> {
-        self.param_nr += 1;
+        let input_output = input_output();
 
-        unsafe {
-            SQLBindParameter(
-                self.stmt,
-                self.param_nr,
-                SQL_PARAM_INPUT,
-                SQL_C_CHAR,
-                SQL_VARCHAR,
-                length as SQLULEN,
-                0,
-                value_ptr,
-                1,
-                indicator_ptr,
-            )
+        // Written values are terminated within the bytes reserved by `String`.
+        let buffer_length = match input_output {
+            SQL_PARAM_INPUT => 1,
+            _ => (length + 1) as SQLLEN,
+        };
+
+        for param_nr in self.param_nrs(field)? {
+            let (data_type, param_size, decimal_digits) =
+                self.param_type(field, param_nr, SQL_VARCHAR, length as SQLULEN, 0)?;
+
+            unsafe {
+                SQLBindParameter(
+                    self.stmt,
+                    param_nr,
+                    input_output,
+                    SQL_C_CHAR,
+                    data_type,
+                    param_size,
+                    decimal_digits,
+                    value_ptr,
+                    buffer_length,
+                    indicator_ptr,
+                )
+            }
+            .check()?;
+        }
+
+        Ok(())
+    }
+
+    fn bind_null(&mut self, field: &FieldPath) -> Result<()> {
+        for param_nr in self.param_nrs(field)? {
+            let (data_type, param_size, decimal_digits) =
+                self.param_type(field, param_nr, SQL_VARCHAR, 1, 0)?;
+
+            unsafe {
+                SQLBindParameter(
+                    self.stmt,
+                    param_nr,
+                    SQL_PARAM_INPUT,
+                    SQL_C_CHAR,
+                    data_type,
+                    param_size,
+                    decimal_digits,
+                    null_mut(),
+                    0,
+                    &NULL_INDICATOR as *const SQLLEN as *mut SQLLEN,
+                )
+            }
+            .check()?;
+        }
+
+        Ok(())
+    }
+
+    fn value_dependent(&mut self, field: &FieldPath) -> Result<()> {
+        if input_output() != SQL_PARAM_INPUT {
+            return Err(Error::Bind(format!(
+                "field {} is an Option which cannot be bound to an output parameter, use Nullable instead",
+                field
+            )));
+        }
+
+        self.value_dependent = true;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        col_binding::{Cols, NoCols},
+        connection::{Connection, Environment},
+        error::Error,
+        input_output::Out,
+        nullable::Nullable,
+        param_binding::{NoParams, ParamSet, Params},
+        statement::Statement,
+        tests::CONN_STR,
+    };
+
+    #[test]
+    fn bind_described_params() {
+        let env = Environment::new().unwrap();
+        let conn = Connection::new(&env, CONN_STR).unwrap();
+
+        {
+            let mut stmt: Statement<Params<(i32, i32)>, Cols<i32>> =
+                Statement::new(&conn, "SELECT ? + ?").unwrap();
+            stmt.set_describe_params(true);
+            *stmt.params() = (23, 42);
+            stmt.exec().unwrap();
+            assert!(stmt.fetch().unwrap());
+            assert_eq!(65, *stmt.cols());
+        }
+
+        {
+            let mut stmt: Statement<Params<i32>, NoCols> =
+                Statement::new(&conn, "SELECT ?, ?").unwrap();
+            stmt.set_describe_params(true);
+            match stmt.exec() {
+                Err(Error::Bind(_)) => (),
+                _ => panic!("missing parameter not detected"),
+            }
+        }
+    }
+
+    #[test]
+    fn bind_option_params() {
+        type Args = (Option<i32>, Option<i32>);
+        type Row = (Nullable<i32>, Nullable<i32>);
+
+        let env = Environment::new().unwrap();
+        let conn = Connection::new(&env, CONN_STR).unwrap();
+
+        {
+            let mut stmt: Statement<Params<Args>, Cols<Row>> =
+                Statement::new(&conn, "SELECT ?, ?").unwrap();
+
+            *stmt.params() = (Some(23), None);
+            stmt.exec().unwrap();
+            assert!(stmt.fetch().unwrap());
+            assert_eq!(Some(&23), stmt.cols().0.as_ref());
+            assert_eq!(None, stmt.cols().1.as_ref());
+
+            *stmt.params() = (None, Some(42));
+            stmt.exec().unwrap();
+            assert!(stmt.fetch().unwrap());
+            assert_eq!(None, stmt.cols().0.as_ref());
+            assert_eq!(Some(&42), stmt.cols().1.as_ref());
+        }
+
+        {
+            let mut stmt: Statement<NoParams, Cols<Option<i32>>> =
+                Statement::new(&conn, "SELECT 1").unwrap();
+            match stmt.exec() {
+                Err(Error::Bind(msg)) => assert!(msg.contains("Nullable")),
+                _ => panic!("Option column not rejected"),
+            }
+        }
+
+        {
+            let mut stmt: Statement<Params<Out<Option<i32>>>, NoCols> =
+                Statement::new(&conn, "SELECT ?").unwrap();
+            match stmt.exec() {
+                Err(Error::Bind(_)) => (),
+                _ => panic!("Option output parameter not rejected"),
+            }
+        }
+
+        {
+            let mut stmt: Statement<ParamSet<Option<i32>>, NoCols> =
+                Statement::new(&conn, "SELECT ?").unwrap();
+            stmt.params().push(None);
+            match stmt.exec() {
+                Err(Error::Bind(_)) => (),
+                _ => panic!("Option in parameter set not rejected"),
+            }
         }
-        .check()
     }
 }
@@ -14,16 +14,19 @@ GNU Lesser General Public License for more details.
 You should have received a copy of the GNU Lesser General Public License
 along with serde-odbc.  If not, see <http://www.gnu.org/licenses/>.
 */
+use std::marker::PhantomData;
 use std::mem::size_of;
 use std::ptr::null;
 
+use super::bindable::{BindWith, Serde};
+use super::binder::SharedIndicators;
+use super::dynamic::Value;
+use super::error::{Error, OdbcResult, Result};
+use super::param_binder::{bind_values, ParamOptions};
 use odbc_sys::{
-    SQLSetStmtAttr, SQLHSTMT, SQLPOINTER, SQL_ATTR_PARAMSET_SIZE, SQL_ATTR_PARAM_BIND_TYPE,
+    SQLFreeStmt, SQLSetStmtAttr, SQLHSTMT, SQLLEN, SQLPOINTER, SQL_ATTR_PARAMSET_SIZE,
+    SQL_ATTR_PARAM_BIND_TYPE, SQL_RESET_PARAMS,
 };
-use serde::ser::Serialize;
-
-use super::error::{OdbcResult, Result};
-use super::param_binder::bind_params;
 
 pub trait ParamBinding {
     fn new() -> Self;
@@ -31,29 +34,44 @@ pub trait ParamBinding {
     type Params;
     fn params(&mut self) -> &mut Self::Params;
 
-    unsafe fn bind(&mut self, stmt: SQLHSTMT) -> Result<()>;
+    unsafe fn bind(&mut self, stmt: SQLHSTMT, options: &ParamOptions) -> Result<()>;
+
+    fn executed(&mut self);
+
+    fn invalidate(&mut self);
 }
 
-pub struct Params<P: Copy + Default + Serialize> {
+pub struct Params<P: Copy + Default, B: BindWith<P> = Serde> {
     data: P,
     last_data: *const P,
+    shared: SharedIndicators,
+    _binding: PhantomData<B>,
 }
 
 pub struct NoParams {
     data: (),
 }
 
-pub struct ParamSet<P: Copy + Serialize> {
+pub struct ParamSet<P: Copy, B: BindWith<P> = Serde> {
     data: Vec<P>,
     last_data: *const P,
     last_size: usize,
+    shared: SharedIndicators,
+    _binding: PhantomData<B>,
 }
 
-impl<P: Copy + Default + Serialize> ParamBinding for Params<P> {
+pub struct DynParams {
+    data: Vec<Value>,
+    indicators: Vec<SQLLEN>,
+}
+
+impl<P: Copy + Default, B: BindWith<P>> ParamBinding for Params<P, B> {
     fn new() -> Self {
         Params {
             data: Default::default(),
             last_data: null(),
+            shared: Default::default(),
+            _binding: PhantomData,
         }
     }
 
@@ -62,16 +80,30 @@ impl<P: Copy + Default + Serialize> ParamBinding for Params<P> {
         &mut self.data
     }
 
-    unsafe fn bind(&mut self, stmt: SQLHSTMT) -> Result<()> {
+    unsafe fn bind(&mut self, stmt: SQLHSTMT, options: &ParamOptions) -> Result<()> {
         let data = &self.data as *const P;
 
         if self.last_data != data {
-            bind_params(stmt, &*data)?;
-            self.last_data = data;
+            // Bindings which depend on the current values are redone on every call.
+            let (value_dependent, shared) = B::bind_params(stmt, &*data, options)?;
+            self.last_data = if value_dependent { null() } else { data };
+            self.shared = shared;
         }
 
+        self.shared.write(&mut self.data as *mut P as *mut u8);
+
         Ok(())
     }
+
+    fn executed(&mut self) {
+        if self.shared.has_output() {
+            unsafe { self.shared.read(&mut self.data as *mut P as *mut u8) };
+        }
+    }
+
+    fn invalidate(&mut self) {
+        self.last_data = null();
+    }
 }
 
 impl ParamBinding for NoParams {
@@ -84,17 +116,47 @@ impl ParamBinding for NoParams {
         &mut self.data
     }
 
-    unsafe fn bind(&mut self, _stmt: SQLHSTMT) -> Result<()> {
+    unsafe fn bind(&mut self, _stmt: SQLHSTMT, _options: &ParamOptions) -> Result<()> {
         Ok(())
     }
+
+    fn executed(&mut self) {}
+
+    fn invalidate(&mut self) {}
+}
+
+impl ParamBinding for DynParams {
+    fn new() -> Self {
+        DynParams {
+            data: Vec::new(),
+            indicators: Vec::new(),
+        }
+    }
+
+    type Params = Vec<Value>;
+    fn params(&mut self) -> &mut Self::Params {
+        &mut self.data
+    }
+
+    unsafe fn bind(&mut self, stmt: SQLHSTMT, options: &ParamOptions) -> Result<()> {
+        SQLFreeStmt(stmt, SQL_RESET_PARAMS).check()?;
+
+        bind_values(stmt, &self.data, &mut self.indicators, options)
+    }
+
+    fn executed(&mut self) {}
+
+    fn invalidate(&mut self) {}
 }
 
-impl<P: Copy + Serialize> ParamBinding for ParamSet<P> {
+impl<P: Copy, B: BindWith<P>> ParamBinding for ParamSet<P, B> {
     fn new() -> Self {
         ParamSet {
             data: Vec::new(),
             last_data: null(),
             last_size: 0,
+            shared: Default::default(),
+            _binding: PhantomData,
         }
     }
 
@@ -103,13 +165,25 @@ impl<P: Copy + Serialize> ParamBinding for ParamSet<P> {
         &mut self.data
     }
 
-    unsafe fn bind(&mut self, stmt: SQLHSTMT) -> Result<()> {
+    unsafe fn bind(&mut self, stmt: SQLHSTMT, options: &ParamOptions) -> Result<()> {
         let data = self.data.first().unwrap() as *const P;
         let size = self.data.len();
 
         if self.last_data != data {
-            bind_params(stmt, &*data)?;
+            let (value_dependent, shared) = B::bind_params(stmt, &*data, options)?;
+
+            if value_dependent {
+                return Err(Error::Bind(
+                    "parameter sets cannot contain Option fields, use Nullable instead".to_owned(),
+                ));
+            }
+
             self.last_data = data;
+            self.shared = shared;
+        }
+
+        for params in &mut self.data {
+            self.shared.write(params as *mut P as *mut u8);
         }
 
         if self.last_size != size {
@@ -119,9 +193,22 @@ impl<P: Copy + Serialize> ParamBinding for ParamSet<P> {
 
         Ok(())
     }
+
+    fn executed(&mut self) {
+        if self.shared.has_output() {
+            for params in &mut self.data {
+                unsafe { self.shared.read(params as *mut P as *mut u8) };
+            }
+        }
+    }
+
+    fn invalidate(&mut self) {
+        self.last_data = null();
+        self.last_size = 0;
+    }
 }
 
-impl<P: Copy + Serialize> ParamSet<P> {
+impl<P: Copy, B: BindWith<P>> ParamSet<P, B> {
     unsafe fn bind_param_set(stmt: SQLHSTMT, size: usize) -> Result<()> {
         SQLSetStmtAttr(
             stmt,
@@ -139,10 +226,15 @@ impl<P: Copy + Serialize> ParamSet<P> {
 mod tests {
     use super::*;
 
+    use generic_array::typenum::U8;
+
     use crate::{
         col_binding::{Cols, NoCols},
         connection::{Connection, Environment},
+        input_output::Out,
+        nullable::Nullable,
         statement::Statement,
+        string::String,
         tests::CONN_STR,
     };
 
@@ -177,4 +269,26 @@ mod tests {
             assert!(!stmt.fetch().unwrap());
         }
     }
+
+    #[test]
+    fn read_output_str() {
+        let env = Environment::new().unwrap();
+        let conn = Connection::new(&env, CONN_STR).unwrap();
+
+        type Call = (Out<Nullable<String<U8>>>, String<U8>);
+
+        let mut stmt: Statement<Params<Call>, NoCols> =
+            Statement::new(&conn, "{? = CALL NULLIF(?, 'bar')}").unwrap();
+
+        // Stale values are replaced by the ones written by the driver.
+        *stmt.params().0 = Nullable::from(Some(String::default()));
+        stmt.params().1.extend_from_slice(b"foo");
+        stmt.exec().unwrap();
+        assert_eq!(&b"foo"[..], stmt.params().0.as_ref().unwrap().as_slice());
+
+        stmt.params().1.clear();
+        stmt.params().1.extend_from_slice(b"bar");
+        stmt.exec().unwrap();
+        assert!(stmt.params().0.as_ref().is_none());
+    }
 }
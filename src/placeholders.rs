@@ -0,0 +1,307 @@
+/*
+This file is part of serde-odbc.
+
+serde-odbc is free software: you can redistribute it and/or modify
+it under the terms of the GNU Lesser General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+serde-odbc is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Lesser General Public License for more details.
+
+You should have received a copy of the GNU Lesser General Public License
+along with serde-odbc.  If not, see <http://www.gnu.org/licenses/>.
+*/
+use std::iter::Peekable;
+use std::str::Chars;
+
+use crate::error::{Error, Result};
+
+pub fn parse_placeholders(stmt_str: &str) -> Result<(String, Vec<String>)> {
+    let mut names = Vec::new();
+
+    let (parsed, positional) = rewrite_placeholders(stmt_str, |parsed, name| {
+        parsed.push('?');
+        names.push(name.to_owned());
+    });
+
+    if positional && !names.is_empty() {
+        return Err(Error::Bind(
+            "statement mixes positional and named parameters".to_owned(),
+        ));
+    }
+
+    Ok((parsed, names))
+}
+
+// Expands the list parameter `name` into `len` placeholders. As `DynParams`
+// are bound by position, all placeholders become positional and the returned
+// names give the parameter each of them refers to.
+pub fn expand_placeholder(stmt_str: &str, name: &str, len: usize) -> Result<(String, Vec<String>)> {
+    // `IN ()` is invalid and `IN (NULL)` would make `NOT IN` match no rows.
+    if len == 0 {
+        return Err(Error::Bind(format!("list parameter :{} is empty", name)));
+    }
+
+    let mut names = Vec::new();
+    let mut expanded_list = false;
+
+    let (expanded, positional) = rewrite_placeholders(stmt_str, |expanded, other_name| {
+        let count = if other_name.eq_ignore_ascii_case(name) {
+            expanded_list = true;
+            len
+        } else {
+            1
+        };
+
+        for i in 0..count {
+            if i != 0 {
+                expanded.push_str(", ");
+            }
+
+            expanded.push('?');
+            names.push(other_name.to_owned());
+        }
+    });
+
+    if positional && !names.is_empty() {
+        return Err(Error::Bind(
+            "statement mixes positional and named parameters".to_owned(),
+        ));
+    }
+
+    if !expanded_list {
+        return Err(Error::Bind(format!(
+            "list parameter :{} does not occur in the statement",
+            name
+        )));
+    }
+
+    Ok((expanded, names))
+}
+
+fn rewrite_placeholders<F>(stmt_str: &str, mut f: F) -> (String, bool)
+where
+    F: FnMut(&mut String, &str),
+{
+    let mut parsed = String::with_capacity(stmt_str.len());
+    let mut positional = false;
+
+    let mut chars = stmt_str.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' | '"' => {
+                parsed.push(c);
+                copy_quoted(&mut chars, &mut parsed, c);
+            }
+            '-' if chars.peek() == Some(&'-') => {
+                parsed.push(c);
+                parsed.push(chars.next().unwrap());
+                copy_until(&mut chars, &mut parsed, "\n");
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                parsed.push(c);
+                parsed.push(chars.next().unwrap());
+                copy_until(&mut chars, &mut parsed, "*/");
+            }
+            ':' if chars.peek() == Some(&':') => {
+                parsed.push(c);
+                parsed.push(chars.next().unwrap());
+            }
+            ':' if matches!(chars.peek(), Some(c) if is_ident_start(*c)) => {
+                let mut name = String::new();
+
+                while let Some(c) = chars.peek().filter(|c| is_ident(**c)) {
+                    name.push(*c);
+                    chars.next();
+                }
+
+                f(&mut parsed, &name);
+            }
+            '?' => {
+                parsed.push(c);
+                positional = true;
+            }
+            _ => parsed.push(c),
+        }
+    }
+
+    (parsed, positional)
+}
+
+fn copy_quoted(chars: &mut Peekable<Chars>, parsed: &mut String, quote: char) {
+    while let Some(c) = chars.next() {
+        parsed.push(c);
+
+        if c == quote {
+            if chars.peek() != Some(&quote) {
+                break;
+            }
+
+            parsed.push(chars.next().unwrap());
+        }
+    }
+}
+
+fn copy_until(chars: &mut Peekable<Chars>, parsed: &mut String, end: &str) {
+    let start = parsed.len();
+
+    for c in chars {
+        parsed.push(c);
+
+        if parsed[start..].ends_with(end) {
+            break;
+        }
+    }
+}
+
+fn is_ident_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_'
+}
+
+fn is_ident(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde::Serialize;
+
+    use crate::{
+        col_binding::{Cols, NoCols},
+        connection::{Connection, Environment},
+        dynamic::Value,
+        param_binding::{DynParams, NoParams, ParamSet, Params},
+        statement::Statement,
+        tests::CONN_STR,
+    };
+
+    #[test]
+    fn parse_named_params() {
+        let (parsed, names) = parse_placeholders(
+            "SELECT ':foo', \"::bar\", x::TEXT -- :baz\n, :foo /* :qux */ FROM tbl WHERE id = :id_2",
+        )
+        .unwrap();
+
+        assert_eq!(
+            "SELECT ':foo', \"::bar\", x::TEXT -- :baz\n, ? /* :qux */ FROM tbl WHERE id = ?",
+            parsed
+        );
+        assert_eq!(vec!["foo", "id_2"], names);
+
+        assert!(parse_placeholders("SELECT :foo, ?").is_err());
+    }
+
+    #[test]
+    fn expand_list_param() {
+        let (expanded, names) = expand_placeholder(
+            "SELECT ':ids' FROM tbl WHERE id IN (:ids) AND id <> :id",
+            "ids",
+            3,
+        )
+        .unwrap();
+
+        assert_eq!(
+            "SELECT ':ids' FROM tbl WHERE id IN (?, ?, ?) AND id <> ?",
+            expanded
+        );
+        assert_eq!(vec!["ids", "ids", "ids", "id"], names);
+
+        assert!(expand_placeholder("SELECT id FROM tbl WHERE id NOT IN (:ids)", "ids", 0).is_err());
+        assert!(expand_placeholder("SELECT id FROM tbl WHERE id IN (:ids, ?)", "ids", 1).is_err());
+        assert!(expand_placeholder("SELECT id FROM tbl WHERE id = :id", "ids", 1).is_err());
+    }
+
+    #[test]
+    fn bind_dyn_params() {
+        let env = Environment::new().unwrap();
+        let conn = Connection::new(&env, CONN_STR).unwrap();
+
+        {
+            let mut stmt: Statement<NoParams, NoCols> =
+                Statement::new(&conn, "CREATE TEMPORARY TABLE tbl (id INTEGER NOT NULL)").unwrap();
+            stmt.exec().unwrap();
+        }
+
+        {
+            let mut stmt: Statement<ParamSet<i32>, NoCols> =
+                Statement::new(&conn, "INSERT INTO tbl (id) VALUES (?)").unwrap();
+            stmt.params().extend(0..10);
+            stmt.exec().unwrap();
+        }
+
+        let (stmt_str, names) = expand_placeholder(
+            "SELECT id FROM tbl WHERE id IN (:ids) AND id <> :id ORDER BY id",
+            "ids",
+            3,
+        )
+        .unwrap();
+
+        let mut ids = vec![Value::from(3), Value::from(5), Value::Null].into_iter();
+        let values = names
+            .iter()
+            .map(|name| match name.as_str() {
+                "ids" => ids.next().unwrap(),
+                _ => Value::from(5),
+            })
+            .collect();
+
+        let mut stmt: Statement<DynParams, Cols<i32>> = Statement::new(&conn, &stmt_str).unwrap();
+        assert_eq!(vec![3], stmt.query_all(values).unwrap());
+
+        let (stmt_str, _) = expand_placeholder(
+            "SELECT id FROM tbl WHERE id NOT IN (:ids) ORDER BY id",
+            "ids",
+            8,
+        )
+        .unwrap();
+
+        let mut stmt: Statement<DynParams, Cols<i32>> = Statement::new(&conn, &stmt_str).unwrap();
+        let ids = stmt.query_all((0..8).map(Value::from).collect()).unwrap();
+        assert_eq!(vec![8, 9], ids);
+    }
+
+    #[test]
+    fn bind_named_params() {
+        #[derive(Clone, Copy, Default, Serialize)]
+        struct Args {
+            foo: i32,
+            bar: i32,
+        }
+
+        let env = Environment::new().unwrap();
+        let conn = Connection::new(&env, CONN_STR).unwrap();
+
+        let mut stmt: Statement<Params<Args>, Cols<(i32, i32, i32)>> =
+            Statement::with_named_params(&conn, "SELECT :bar, :foo, :bar").unwrap();
+        stmt.params().foo = 23;
+        stmt.params().bar = 42;
+        stmt.exec().unwrap();
+        assert!(stmt.fetch().unwrap());
+        assert_eq!((42, 23, 42), *stmt.cols());
+        assert!(!stmt.fetch().unwrap());
+
+        let mut stmt: Statement<Params<Args>, Cols<i32>> =
+            Statement::with_named_params(&conn, "SELECT :foo").unwrap();
+        match stmt.exec() {
+            Err(Error::Bind(msg)) => assert!(msg.contains("field bar ")),
+            _ => panic!("unmatched field not rejected"),
+        }
+    }
+
+    #[test]
+    fn keep_plain_stmt_str() {
+        let env = Environment::new().unwrap();
+        let conn = Connection::new(&env, CONN_STR).unwrap();
+
+        // SQLite binds `:foo` by position itself as the statement is passed on unchanged.
+        let mut stmt: Statement<Params<i32>, Cols<i32>> =
+            Statement::new(&conn, "SELECT :foo").unwrap();
+        assert_eq!(42, stmt.query_one(42).unwrap());
+    }
+}
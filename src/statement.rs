@@ -15,47 +15,171 @@ You should have received a copy of the GNU Lesser General Public License
 along with serde-odbc.  If not, see <http://www.gnu.org/licenses/>.
 */
 use std::ptr::null_mut;
+use std::time::Duration;
 
 use odbc_sys::{
-    SQLAllocHandle, SQLExecute, SQLFetch, SQLFreeHandle, SQLFreeStmt, SQLPrepare, SQLHANDLE,
-    SQLHSTMT, SQLINTEGER, SQL_CLOSE, SQL_HANDLE_STMT, SQL_NO_DATA,
+    FetchOrientation, SQLAllocHandle, SQLBulkOperations, SQLExecute, SQLFetch, SQLFetchScroll,
+    SQLFreeHandle, SQLFreeStmt, SQLPrepare, SQLSetStmtAttr, SQLHANDLE, SQLHSTMT, SQLINTEGER,
+    SQLLEN, SQLPOINTER, SQLRETURN, SQLULEN, SQLUSMALLINT, SQL_ADD, SQL_ATTR_ROW_ARRAY_SIZE,
+    SQL_CLOSE, SQL_FETCH_ABSOLUTE, SQL_FETCH_FIRST, SQL_FETCH_LAST, SQL_FETCH_PRIOR,
+    SQL_FETCH_RELATIVE, SQL_HANDLE_STMT, SQL_NO_DATA, SQL_RESET_PARAMS, SQL_UNBIND,
 };
-use serde::ser::Serialize;
 
+use super::bindable::BindWith;
+use super::cancel::CancelHandle;
+use super::col_binder::ColOptions;
 use super::col_binding::{ColBinding, RowSet};
 use super::connection::Connection;
-use super::error::{OdbcResult, Result};
+use super::cursor::{Concurrency, CursorType};
+use super::error::{check_stmt, stmt_state, Error, OdbcResult, Result};
+use super::ffi::{
+    self, SQLGetStmtAttr, SQLSetPos, SQL_ATTR_MAX_ROWS, SQL_ATTR_QUERY_TIMEOUT,
+    SQL_ATTR_ROW_NUMBER, SQL_DELETE, SQL_LOCK_NO_CHANGE, SQL_UPDATE,
+};
+use super::metadata::{describe_cols, describe_params, ColumnDescription, ParamDescription};
+use super::param_binder::ParamOptions;
 use super::param_binding::ParamBinding;
+use super::placeholders::parse_placeholders;
 
 pub struct Statement<P: ParamBinding, C: ColBinding> {
     stmt: SQLHSTMT,
     is_positioned: bool,
+    is_exhausted: bool,
+    is_scrollable: bool,
     params: P,
     cols: C,
+    param_options: ParamOptions,
+    col_options: ColOptions,
+    cancel_handle: CancelHandle,
+    max_rows: Option<usize>,
+    rows_fetched: usize,
 }
 
 impl<P: ParamBinding, C: ColBinding> Statement<P, C> {
     pub fn new(conn: &Connection, stmt_str: &str) -> Result<Self> {
+        Self::prepare(conn, stmt_str, Vec::new(), false, &[])
+    }
+
+    // Replaces placeholders like `:name` by `?` and binds fields to them by name.
+    pub fn with_named_params(conn: &Connection, stmt_str: &str) -> Result<Self> {
+        let (stmt_str, names) = parse_placeholders(stmt_str)?;
+
+        Self::prepare(conn, &stmt_str, names, false, &[])
+    }
+
+    pub fn with_cursor_type(
+        conn: &Connection,
+        stmt_str: &str,
+        cursor_type: CursorType,
+    ) -> Result<Self> {
+        Self::prepare(
+            conn,
+            stmt_str,
+            Vec::new(),
+            cursor_type.is_scrollable(),
+            &[cursor_type.attr()],
+        )
+    }
+
+    pub fn with_cursor(
+        conn: &Connection,
+        stmt_str: &str,
+        cursor_type: CursorType,
+        concurrency: Concurrency,
+    ) -> Result<Self> {
+        Self::prepare(
+            conn,
+            stmt_str,
+            Vec::new(),
+            cursor_type.is_scrollable(),
+            &[cursor_type.attr(), concurrency.attr()],
+        )
+    }
+
+    fn prepare(
+        conn: &Connection,
+        stmt_str: &str,
+        names: Vec<String>,
+        is_scrollable: bool,
+        attrs: &[(SQLINTEGER, SQLULEN)],
+    ) -> Result<Self> {
         let mut stmt: SQLHANDLE = null_mut();
 
         unsafe { SQLAllocHandle(SQL_HANDLE_STMT, conn.handle(), &mut stmt) }.check()?;
 
         let stmt = stmt as SQLHSTMT;
 
-        unsafe { SQLPrepare(stmt, stmt_str.as_ptr(), stmt_str.len() as SQLINTEGER) }.check()?;
-
-        Ok(Statement {
+        let stmt = Statement {
             stmt,
             is_positioned: false,
+            is_exhausted: false,
+            is_scrollable,
             params: P::new(),
             cols: C::new(),
-        })
+            param_options: ParamOptions {
+                names,
+                describe: false,
+            },
+            col_options: Default::default(),
+            cancel_handle: CancelHandle::new(stmt),
+            max_rows: None,
+            rows_fetched: 0,
+        };
+
+        for &(attr, value) in attrs {
+            unsafe { ffi::SQLSetStmtAttr(stmt.stmt, attr, value as SQLPOINTER, 0) }.check()?;
+        }
+
+        unsafe { SQLPrepare(stmt.stmt, stmt_str.as_ptr(), stmt_str.len() as SQLINTEGER) }
+            .check()?;
+
+        Ok(stmt)
     }
 
     pub fn handle(&self) -> SQLHANDLE {
         self.stmt as SQLHANDLE
     }
 
+    pub fn cancel_handle(&self) -> CancelHandle {
+        self.cancel_handle.clone()
+    }
+
+    pub fn set_query_timeout(&mut self, timeout: Option<Duration>) -> Result<()> {
+        let secs = timeout.map_or(0, |timeout| {
+            timeout.as_secs() + (timeout.subsec_nanos() != 0) as u64
+        });
+
+        unsafe { ffi::SQLSetStmtAttr(self.stmt, SQL_ATTR_QUERY_TIMEOUT, secs as SQLPOINTER, 0) }
+            .check()
+    }
+
+    pub fn max_rows(&self) -> Option<usize> {
+        self.max_rows
+    }
+
+    pub fn set_max_rows(&mut self, max_rows: Option<usize>) -> Result<()> {
+        let rc = unsafe {
+            ffi::SQLSetStmtAttr(
+                self.stmt,
+                SQL_ATTR_MAX_ROWS,
+                max_rows.unwrap_or(0) as SQLPOINTER,
+                0,
+            )
+        };
+
+        // Drivers which do not support this or change the value, i.e. report
+        // HYC00 or 01S02, are limited by `fetch` instead.
+        if let Err(err) = rc.check() {
+            if unsafe { stmt_state(self.handle()) } != Some(*b"HYC00") {
+                return Err(err);
+            }
+        }
+
+        self.max_rows = max_rows;
+
+        Ok(())
+    }
+
     pub fn params(&mut self) -> &mut P::Params {
         self.params.params()
     }
@@ -64,33 +188,337 @@ impl<P: ParamBinding, C: ColBinding> Statement<P, C> {
         self.cols.cols()
     }
 
+    pub fn rows_mut(&mut self) -> &mut [C::Row] {
+        self.cols.rows_mut()
+    }
+
+    pub fn columns(&self) -> Result<Vec<ColumnDescription>> {
+        unsafe { describe_cols(self.stmt) }
+    }
+
+    pub fn parameters(&self) -> Result<Vec<ParamDescription>> {
+        unsafe { describe_params(self.stmt) }
+    }
+
+    pub fn describe_params(&self) -> bool {
+        self.param_options.describe
+    }
+
+    pub fn set_describe_params(&mut self, describe: bool) {
+        self.param_options.describe = describe;
+    }
+
+    pub fn validate_cols(&self) -> bool {
+        self.col_options.validate
+    }
+
+    pub fn set_validate_cols(&mut self, validate: bool) {
+        self.col_options.validate = validate;
+    }
+
+    pub fn bind_cols_by_name(&self) -> bool {
+        self.col_options.by_name
+    }
+
+    pub fn set_bind_cols_by_name(&mut self, by_name: bool) {
+        self.col_options.by_name = by_name;
+    }
+
     pub fn exec(&mut self) -> Result<()> {
+        self.close_cursor()?;
+
+        self.is_exhausted = false;
+        self.rows_fetched = 0;
+
+        unsafe {
+            self.params.bind(self.stmt, &self.param_options)?;
+            self.cols.bind(self.stmt, self.col_options)?;
+        }
+
+        self.cancel_handle.reset();
+
+        let result = unsafe { check_stmt(SQLExecute(self.stmt), self.handle()) };
+        self.cancel_handle.check(result)?;
+
+        self.params.executed();
+
+        Ok(())
+    }
+
+    pub fn fetch(&mut self) -> Result<bool> {
+        if self.is_exhausted {
+            return Ok(false);
+        }
+
+        let remaining_rows = self.max_rows.map_or(usize::MAX, |max_rows| {
+            max_rows.saturating_sub(self.rows_fetched)
+        });
+
+        if remaining_rows == 0 || !self.fetched(|stmt| unsafe { SQLFetch(stmt) })? {
+            self.is_exhausted = true;
+
+            // Scrollable cursors stay open so that callers can scroll back.
+            if !self.is_scrollable {
+                self.close_cursor()?;
+            }
+
+            return Ok(false);
+        }
+
+        self.cols.truncate(remaining_rows);
+        self.rows_fetched += self.cols.rows().len();
+
+        Ok(true)
+    }
+
+    pub fn close_cursor(&mut self) -> Result<()> {
         if self.is_positioned {
             unsafe { SQLFreeStmt(self.stmt, SQL_CLOSE) }.check()?;
 
             self.is_positioned = false;
         }
 
+        Ok(())
+    }
+
+    // Restores the settings of a newly prepared statement, e.g. before it is cached.
+    pub(crate) fn reset(&mut self) -> Result<()> {
+        self.close_cursor()?;
+
+        self.set_max_rows(None)?;
+        self.set_query_timeout(None)?;
+
+        self.param_options.describe = false;
+        self.params.invalidate();
+
+        // Also drops any row set buffers to restore the default fetch size.
+        self.col_options = Default::default();
+        self.unbind()?;
+        self.cols = C::new();
+
+        // Handles given out before neither cancel the next use nor are pending anymore.
+        self.cancel_handle.invalidate();
+        self.cancel_handle = CancelHandle::new(self.stmt);
+
+        Ok(())
+    }
+
+    pub fn reset_params(&mut self) -> Result<()> {
+        unsafe { SQLFreeStmt(self.stmt, SQL_RESET_PARAMS) }.check()?;
+
+        self.params.invalidate();
+
+        Ok(())
+    }
+
+    pub fn unbind(&mut self) -> Result<()> {
+        unsafe { SQLFreeStmt(self.stmt, SQL_UNBIND) }.check()?;
+
+        self.cols.invalidate();
+
+        Ok(())
+    }
+
+    pub fn query_one(&mut self, params: P::Params) -> Result<C::Row>
+    where
+        C::Row: Clone,
+    {
+        self.query(params, |rows| rows.one())
+    }
+
+    pub fn query_optional(&mut self, params: P::Params) -> Result<Option<C::Row>>
+    where
+        C::Row: Clone,
+    {
+        self.query(params, |mut rows| {
+            let row = rows.next().transpose()?;
+
+            if row.is_some() && rows.next().transpose()?.is_some() {
+                return Err(Error::TooManyRows);
+            }
+
+            Ok(row)
+        })
+    }
+
+    pub fn query_all(&mut self, params: P::Params) -> Result<Vec<C::Row>>
+    where
+        C::Row: Clone,
+    {
+        self.query(params, |rows| rows.collect_vec())
+    }
+
+    fn query<T, F>(&mut self, params: P::Params, f: F) -> Result<T>
+    where
+        F: FnOnce(Rows<'_, P, C>) -> Result<T>,
+    {
+        *self.params() = params;
+
+        self.exec()?;
+
+        let result = f(self.rows());
+        let closed = self.close_cursor();
+
+        let value = result?;
+        closed?;
+
+        Ok(value)
+    }
+
+    pub fn rows(&mut self) -> Rows<'_, P, C> {
+        Rows {
+            stmt: self,
+            pos: 0,
+            state: RowsState::Initial,
+        }
+    }
+
+    pub fn fetch_first(&mut self) -> Result<bool> {
+        self.fetch_scroll(SQL_FETCH_FIRST, 0)
+    }
+
+    pub fn fetch_last(&mut self) -> Result<bool> {
+        self.fetch_scroll(SQL_FETCH_LAST, 0)
+    }
+
+    pub fn fetch_prior(&mut self) -> Result<bool> {
+        self.fetch_scroll(SQL_FETCH_PRIOR, 0)
+    }
+
+    pub fn fetch_absolute(&mut self, row_nr: isize) -> Result<bool> {
+        self.fetch_scroll(SQL_FETCH_ABSOLUTE, row_nr as SQLLEN)
+    }
+
+    pub fn fetch_relative(&mut self, offset: isize) -> Result<bool> {
+        self.fetch_scroll(SQL_FETCH_RELATIVE, offset as SQLLEN)
+    }
+
+    fn fetch_scroll(&mut self, orientation: FetchOrientation, offset: SQLLEN) -> Result<bool> {
+        // Forward-only cursors are already closed when exhausted.
+        if self.is_exhausted && !self.is_scrollable {
+            return Ok(false);
+        }
+
+        if !self.fetched(|stmt| unsafe { SQLFetchScroll(stmt, orientation, offset) })? {
+            return Ok(false);
+        }
+
+        if let Some(max_rows) = self.max_rows {
+            let row_nr = self.row_number()?;
+
+            // Drivers ignoring SQL_ATTR_MAX_ROWS fetch the last rows beyond the limit.
+            if orientation == SQL_FETCH_LAST && row_nr + self.cols.rows().len() > max_rows + 1 {
+                let row_nr = (max_rows + 1)
+                    .saturating_sub(self.cols.row_array_size())
+                    .max(1);
+
+                return self.fetch_scroll(SQL_FETCH_ABSOLUTE, row_nr as SQLLEN);
+            }
+
+            if row_nr > max_rows {
+                self.is_exhausted = true;
+
+                return Ok(false);
+            }
+
+            self.cols.truncate(max_rows - row_nr + 1);
+            self.rows_fetched = row_nr - 1 + self.cols.rows().len();
+        }
+
+        self.is_exhausted = false;
+
+        Ok(true)
+    }
+
+    fn row_number(&self) -> Result<usize> {
+        let mut row_nr: SQLULEN = 0;
+
         unsafe {
-            self.params.bind(self.stmt)?;
-            self.cols.bind(self.stmt)?;
+            SQLGetStmtAttr(
+                self.stmt,
+                SQL_ATTR_ROW_NUMBER,
+                (&mut row_nr as *mut SQLULEN) as SQLPOINTER,
+                0,
+                null_mut(),
+            )
         }
+        .check()?;
 
-        unsafe { SQLExecute(self.stmt) }.check()
+        Ok(row_nr as usize)
     }
 
-    pub fn fetch(&mut self) -> Result<bool> {
-        let rc = unsafe { SQLFetch(self.stmt) };
+    pub fn update_row(&mut self, row_nr: usize) -> Result<()> {
+        self.set_pos(row_nr, SQL_UPDATE)
+    }
+
+    pub fn delete_row(&mut self, row_nr: usize) -> Result<()> {
+        self.set_pos(row_nr, SQL_DELETE)
+    }
+
+    fn set_pos(&mut self, row_nr: usize, operation: SQLUSMALLINT) -> Result<()> {
+        self.cols.flush();
+
+        unsafe {
+            SQLSetPos(
+                self.stmt,
+                (row_nr + 1) as SQLULEN,
+                operation,
+                SQL_LOCK_NO_CHANGE,
+            )
+        }
+        .check()
+    }
+
+    // Stages the rows into the bound buffers, as many at a time as they hold,
+    // and adds them to the result set of the current query.
+    pub fn add_rows(&mut self, mut rows: &[C::Row]) -> Result<()> {
+        let row_array_size = self.cols.row_array_size();
+
+        while !rows.is_empty() {
+            let staged = self.cols.stage(rows)?;
+
+            unsafe {
+                SQLSetStmtAttr(self.stmt, SQL_ATTR_ROW_ARRAY_SIZE, staged as SQLPOINTER, 0)
+                    .check()?;
+
+                let rc = SQLBulkOperations(self.stmt, SQL_ADD);
 
-        rc.check()?;
+                let reset_rc = SQLSetStmtAttr(
+                    self.stmt,
+                    SQL_ATTR_ROW_ARRAY_SIZE,
+                    row_array_size as SQLPOINTER,
+                    0,
+                );
+
+                check_stmt(rc, self.handle())?;
+                reset_rc.check()?;
+            }
+
+            rows = &rows[staged..];
+        }
+
+        Ok(())
+    }
+
+    fn fetched<F>(&mut self, fetch: F) -> Result<bool>
+    where
+        F: FnOnce(SQLHSTMT) -> SQLRETURN,
+    {
+        // Requests to cancel earlier calls do not affect this one.
+        self.cancel_handle.reset();
+
+        let rc = fetch(self.stmt);
+
+        let result = unsafe { check_stmt(rc, self.handle()) };
+        self.cancel_handle.check(result)?;
 
         self.is_positioned = true;
 
-        Ok(rc != SQL_NO_DATA && self.cols.fetch())
+        Ok(rc != SQL_NO_DATA && self.cols.fetch()?)
     }
 }
 
-impl<P: ParamBinding, C: Default + Copy + Serialize> Statement<P, RowSet<C>> {
+impl<P: ParamBinding, C: Default + Copy, B: BindWith<C>> Statement<P, RowSet<C, B>> {
     pub fn with_fetch_size(conn: &Connection, stmt_str: &str, fetch_size: usize) -> Result<Self> {
         let mut stmt = Self::new(conn, stmt_str)?;
 
@@ -108,8 +536,82 @@ impl<P: ParamBinding, C: Default + Copy + Serialize> Statement<P, RowSet<C>> {
     }
 }
 
+pub struct Rows<'a, P: ParamBinding, C: ColBinding> {
+    stmt: &'a mut Statement<P, C>,
+    pos: usize,
+    state: RowsState,
+}
+
+enum RowsState {
+    Initial,
+    Fetched,
+    Done,
+}
+
+impl<P: ParamBinding, C: ColBinding> Iterator for Rows<'_, P, C>
+where
+    C::Row: Clone,
+{
+    type Item = Result<C::Row>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.state {
+                RowsState::Initial => (),
+                RowsState::Fetched => {
+                    if let Some(row) = self.stmt.cols.rows().get(self.pos) {
+                        self.pos += 1;
+                        return Some(Ok(row.clone()));
+                    }
+                }
+                RowsState::Done => return None,
+            }
+
+            match self.stmt.fetch() {
+                Ok(true) => {
+                    self.pos = 0;
+                    self.state = RowsState::Fetched;
+                }
+                Ok(false) => {
+                    self.state = RowsState::Done;
+                    return None;
+                }
+                Err(err) => {
+                    self.state = RowsState::Done;
+                    return Some(Err(err));
+                }
+            }
+        }
+    }
+}
+
+impl<P: ParamBinding, C: ColBinding> Rows<'_, P, C>
+where
+    C::Row: Clone,
+{
+    pub fn collect_vec(self) -> Result<Vec<C::Row>> {
+        self.collect()
+    }
+
+    pub fn first(mut self) -> Result<Option<C::Row>> {
+        self.next().transpose()
+    }
+
+    pub fn one(mut self) -> Result<C::Row> {
+        let row = self.next().ok_or(Error::NoRows)??;
+
+        match self.next() {
+            None => Ok(row),
+            Some(Ok(_)) => Err(Error::TooManyRows),
+            Some(Err(err)) => Err(err),
+        }
+    }
+}
+
 impl<P: ParamBinding, C: ColBinding> Drop for Statement<P, C> {
     fn drop(&mut self) {
+        self.cancel_handle.invalidate();
+
         let _ = unsafe { SQLFreeHandle(SQL_HANDLE_STMT, self.handle()) };
     }
 }
@@ -119,7 +621,10 @@ mod tests {
     use super::*;
 
     use crate::{
-        col_binding::Cols, connection::Environment, param_binding::Params, tests::CONN_STR,
+        col_binding::{Cols, NoCols},
+        connection::Environment,
+        param_binding::{NoParams, ParamSet, Params},
+        tests::CONN_STR,
     };
 
     #[test]
@@ -135,4 +640,164 @@ mod tests {
         assert_eq!(42, *stmt.cols());
         assert!(!stmt.fetch().unwrap());
     }
+
+    #[test]
+    fn limit_max_rows() {
+        let env = Environment::new().unwrap();
+        let conn = Connection::new(&env, CONN_STR).unwrap();
+
+        {
+            let mut stmt: Statement<NoParams, NoCols> =
+                Statement::new(&conn, "CREATE TEMPORARY TABLE tbl (col INTEGER NOT NULL)").unwrap();
+            stmt.exec().unwrap();
+        }
+
+        {
+            let mut stmt: Statement<ParamSet<i32>, NoCols> =
+                Statement::new(&conn, "INSERT INTO tbl (col) VALUES (?)").unwrap();
+            stmt.params().extend(0..10);
+            stmt.exec().unwrap();
+        }
+
+        {
+            let mut stmt: Statement<NoParams, Cols<i32>> =
+                Statement::new(&conn, "SELECT col FROM tbl ORDER BY col").unwrap();
+            stmt.set_max_rows(Some(3)).unwrap();
+            stmt.exec().unwrap();
+            for i in 0..3 {
+                assert!(stmt.fetch().unwrap());
+                assert_eq!(i, *stmt.cols());
+            }
+            assert!(!stmt.fetch().unwrap());
+        }
+
+        {
+            let mut stmt: Statement<NoParams, RowSet<i32>> =
+                Statement::with_fetch_size(&conn, "SELECT col FROM tbl ORDER BY col", 4).unwrap();
+            stmt.set_max_rows(Some(6)).unwrap();
+            stmt.exec().unwrap();
+            assert!(stmt.fetch().unwrap());
+            assert_eq!(&[0, 1, 2, 3][..], &stmt.cols()[..]);
+            assert!(stmt.fetch().unwrap());
+            assert_eq!(&[4, 5][..], &stmt.cols()[..]);
+            assert!(!stmt.fetch().unwrap());
+        }
+    }
+
+    #[test]
+    fn iterate_rows() {
+        let env = Environment::new().unwrap();
+        let conn = Connection::new(&env, CONN_STR).unwrap();
+
+        {
+            let mut stmt: Statement<NoParams, NoCols> =
+                Statement::new(&conn, "CREATE TEMPORARY TABLE tbl (col INTEGER NOT NULL)").unwrap();
+            stmt.exec().unwrap();
+        }
+
+        {
+            let mut stmt: Statement<ParamSet<i32>, NoCols> =
+                Statement::new(&conn, "INSERT INTO tbl (col) VALUES (?)").unwrap();
+            stmt.params().extend(0..10);
+            stmt.exec().unwrap();
+        }
+
+        {
+            let mut stmt: Statement<NoParams, RowSet<i32>> =
+                Statement::with_fetch_size(&conn, "SELECT col FROM tbl ORDER BY col", 4).unwrap();
+            stmt.exec().unwrap();
+            assert_eq!(
+                (0..10).collect::<Vec<_>>(),
+                stmt.rows().collect_vec().unwrap()
+            );
+        }
+
+        {
+            let mut stmt: Statement<Params<i32>, Cols<i32>> =
+                Statement::new(&conn, "SELECT col FROM tbl WHERE col >= ? ORDER BY col").unwrap();
+
+            *stmt.params() = 5;
+            stmt.exec().unwrap();
+            assert_eq!(Some(5), stmt.rows().first().unwrap());
+
+            *stmt.params() = 9;
+            stmt.exec().unwrap();
+            assert_eq!(9, stmt.rows().one().unwrap());
+
+            *stmt.params() = 8;
+            stmt.exec().unwrap();
+            match stmt.rows().one() {
+                Err(Error::TooManyRows) => (),
+                _ => panic!("more than one row not detected"),
+            }
+
+            *stmt.params() = 10;
+            stmt.exec().unwrap();
+            match stmt.rows().one() {
+                Err(Error::NoRows) => (),
+                _ => panic!("no rows not detected"),
+            }
+        }
+    }
+
+    #[test]
+    fn query_rows() {
+        let env = Environment::new().unwrap();
+        let conn = Connection::new(&env, CONN_STR).unwrap();
+
+        {
+            let mut stmt: Statement<NoParams, NoCols> =
+                Statement::new(&conn, "CREATE TEMPORARY TABLE tbl (col INTEGER NOT NULL)").unwrap();
+            stmt.exec().unwrap();
+        }
+
+        {
+            let mut stmt: Statement<ParamSet<i32>, NoCols> =
+                Statement::new(&conn, "INSERT INTO tbl (col) VALUES (?)").unwrap();
+            stmt.params().extend(0..10);
+            stmt.exec().unwrap();
+        }
+
+        let mut stmt: Statement<Params<i32>, Cols<i32>> =
+            Statement::new(&conn, "SELECT col FROM tbl WHERE col >= ? ORDER BY col").unwrap();
+
+        assert_eq!(vec![7, 8, 9], stmt.query_all(7).unwrap());
+        assert_eq!(9, stmt.query_one(9).unwrap());
+        assert_eq!(Some(9), stmt.query_optional(9).unwrap());
+        assert_eq!(None, stmt.query_optional(10).unwrap());
+        match stmt.query_optional(8) {
+            Err(Error::TooManyRows) => (),
+            _ => panic!("more than one row not detected"),
+        }
+    }
+
+    #[test]
+    fn close_cursor() {
+        let env = Environment::new().unwrap();
+        let conn = Connection::new(&env, CONN_STR).unwrap();
+
+        let mut stmt: Statement<Params<i32>, Cols<i32>> =
+            Statement::new(&conn, "SELECT ? + 1").unwrap();
+
+        *stmt.params() = 1;
+        stmt.exec().unwrap();
+        assert!(stmt.fetch().unwrap());
+        assert_eq!(2, *stmt.cols());
+        assert!(!stmt.fetch().unwrap());
+        assert!(!stmt.fetch().unwrap());
+
+        *stmt.params() = 2;
+        stmt.exec().unwrap();
+        assert!(stmt.fetch().unwrap());
+        stmt.close_cursor().unwrap();
+        stmt.close_cursor().unwrap();
+
+        stmt.reset_params().unwrap();
+        stmt.unbind().unwrap();
+
+        *stmt.params() = 3;
+        stmt.exec().unwrap();
+        assert!(stmt.fetch().unwrap());
+        assert_eq!(4, *stmt.cols());
+    }
 }
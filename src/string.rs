@@ -15,15 +15,19 @@ You should have received a copy of the GNU Lesser General Public License
 along with serde-odbc.  If not, see <http://www.gnu.org/licenses/>.
 */
 use std::cmp::min;
-use std::mem::MaybeUninit;
+use std::mem::offset_of;
 
 use generic_array::{ArrayLength, GenericArray};
-use odbc_sys::SQLLEN;
-use serde::ser::{Serialize, SerializeStruct, Serializer};
+use odbc_sys::{SQLLEN, SQLULEN, SQL_C_CHAR, SQL_VARCHAR};
+use serde::ser::{Serialize, Serializer};
 
-use crate::binder::with_indicator;
+use crate::bindable::{Bindable, FieldBinder, SqlField};
+use crate::binder::with_length;
+use crate::error;
+use crate::metadata::FieldDescription;
 
 #[derive(Clone)]
+#[repr(transparent)]
 struct ByteArray<N: ArrayLength<u8>>(GenericArray<u8, N>);
 
 impl<N: Clone + ArrayLength<u8>> Copy for ByteArray<N> where N::ArrayType: Copy {}
@@ -34,21 +38,43 @@ impl<N: ArrayLength<u8>> Serialize for ByteArray<N> {
     }
 }
 
+// Drivers terminate character data which is why `length + 1` bytes
+// are bound, including the terminator following the value.
 #[derive(Clone)]
+#[repr(C)]
 pub struct String<N: ArrayLength<u8>> {
     indicator: SQLLEN,
     value: ByteArray<N>,
+    terminator: u8,
 }
 
 impl<N: Clone + ArrayLength<u8>> Copy for String<N> where N::ArrayType: Copy {}
 
 impl<N: ArrayLength<u8>> Serialize for String<N> {
     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
-        let mut serializer = serializer.serialize_struct("String", 1)?;
-        with_indicator(&self.indicator as *const _ as *mut _, || {
-            serializer.serialize_field("value", &self.value)
-        })?;
-        serializer.end()
+        with_length(&self.indicator as *const _ as *mut _, || {
+            serializer.serialize_newtype_struct("String", &self.value)
+        })
+    }
+}
+
+unsafe impl<N: ArrayLength<u8>> Bindable for String<N> {
+    fn bind_fields(binder: &mut FieldBinder<'_>, offset: usize) -> error::Result<()> {
+        binder.with_length(offset + offset_of!(Self, indicator), |binder| {
+            binder.bind_bytes(offset + offset_of!(Self, value), N::to_usize())
+        })
+    }
+}
+
+impl<N: ArrayLength<u8>> SqlField for String<N> {
+    fn describe(name: &'static str) -> FieldDescription {
+        FieldDescription {
+            name,
+            c_data_type: SQL_C_CHAR,
+            data_type: SQL_VARCHAR,
+            column_size: N::to_usize() as SQLULEN,
+            nullable: false,
+        }
     }
 }
 
@@ -58,20 +84,27 @@ impl<N: ArrayLength<u8>> String<N> {
     }
 
     pub fn extend_from_slice(&mut self, value: &[u8]) {
-        let len = min(N::to_usize() - self.indicator as usize, value.len());
+        let old_len = self.len();
+        let len = min(N::to_usize() - old_len, value.len());
 
-        self.value.0.as_mut_slice()[self.indicator as usize..][..len]
-            .copy_from_slice(&value[..len]);
+        self.value.0.as_mut_slice()[old_len..][..len].copy_from_slice(&value[..len]);
 
-        self.indicator += len as SQLLEN;
+        self.indicator = (old_len + len) as SQLLEN;
     }
 
     pub fn as_slice(&self) -> &[u8] {
-        &self.value.0.as_slice()[..self.indicator as usize]
+        &self.value.0.as_slice()[..self.len()]
     }
 
     pub fn as_mut_slice(&mut self) -> &mut [u8] {
-        &mut self.value.0.as_mut_slice()[..self.indicator as usize]
+        let len = self.len();
+        &mut self.value.0.as_mut_slice()[..len]
+    }
+
+    // The driver reports the full length of truncated values
+    // and negative indicators for NULL or unknown lengths.
+    fn len(&self) -> usize {
+        min(self.indicator.max(0) as usize, N::to_usize())
     }
 }
 
@@ -79,10 +112,8 @@ impl<N: ArrayLength<u8>> Default for String<N> {
     fn default() -> Self {
         Self {
             indicator: 0,
-            value: unsafe {
-                #[allow(clippy::uninit_assumed_init)]
-                MaybeUninit::uninit().assume_init()
-            },
+            value: ByteArray(Default::default()),
+            terminator: 0,
         }
     }
 }
@@ -91,11 +122,14 @@ impl<N: ArrayLength<u8>> Default for String<N> {
 mod tests {
     use super::*;
 
+    use std::slice;
+
     use generic_array::typenum::U8;
 
     use crate::{
         col_binding::Cols,
         connection::{Connection, Environment},
+        input_output::Out,
         param_binding::Params,
         statement::Statement,
         tests::CONN_STR,
@@ -114,6 +148,25 @@ mod tests {
         assert_eq!(&b"foobar"[..], value.as_slice());
     }
 
+    #[test]
+    fn write_output_str() {
+        let mut value = (Out::new(String::<U8>::default()), 42_u8);
+        let text: &mut String<U8> = &mut value.0;
+
+        // Fill the buffer bound for output parameters as a driver does
+        // when writing back a value which does not fit.
+        let buffer = unsafe {
+            let buffer_ptr = (text as *mut String<U8>).cast::<u8>();
+            slice::from_raw_parts_mut(buffer_ptr.add(offset_of!(String<U8>, value)), 8 + 1)
+        };
+        buffer[..8].copy_from_slice(b"foobarfo");
+        buffer[8] = 0;
+        text.indicator = 12;
+
+        assert_eq!(&b"foobarfo"[..], value.0.as_slice());
+        assert_eq!(42, value.1);
+    }
+
     #[test]
     fn bind_str() {
         let env = Environment::new().unwrap();